@@ -0,0 +1,372 @@
+// Project configuration (`rvy.toml`).
+//
+// kind = "api"
+// web = "actix"
+// databases = ["postgres", "sqlite"]
+// mod_marker = "// Add your modules here"
+//
+// [server]
+// port = 8080
+// grpc_port = 50051
+//
+// [paths]
+// migrations = "db/migrations"
+//
+// [layers.service]
+// dir = "src/domain/service"
+// file_suffix = "_svc"
+// type_suffix = "Svc"
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::generator::DATABASES;
+use crate::schema::parse_databases;
+
+pub const CONFIG_FILE: &str = "rvy.toml";
+
+pub const DEFAULT_MOD_MARKER: &str = "// Add your modules here";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layer {
+    Service,
+    Usecase,
+    Repository,
+    Data,
+    Handler,
+    Adapter,
+    Factory,
+    Config,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 8] = [
+        Layer::Service,
+        Layer::Usecase,
+        Layer::Repository,
+        Layer::Data,
+        Layer::Handler,
+        Layer::Adapter,
+        Layer::Factory,
+        Layer::Config,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Layer::Service => "service",
+            Layer::Usecase => "usecase",
+            Layer::Repository => "repository",
+            Layer::Data => "data",
+            Layer::Handler => "handler",
+            Layer::Adapter => "adapter",
+            Layer::Factory => "factory",
+            Layer::Config => "config",
+        }
+    }
+
+    // Default (file suffix, type suffix). Adapters are named `{name}_{db}`,
+    // config always lives in `database.rs` and factories export a function,
+    // so they don't take the corresponding suffix.
+    fn default_suffixes(self) -> (Option<&'static str>, Option<&'static str>) {
+        match self {
+            Layer::Service => (Some("_service"), Some("Service")),
+            Layer::Usecase => (Some("_usecase"), Some("Usecase")),
+            Layer::Repository => (Some(""), Some("Repository")),
+            Layer::Data => (Some("_data"), Some("Data")),
+            Layer::Handler => (Some("_handler"), Some("Handler")),
+            Layer::Factory => (Some("_factory"), None),
+            Layer::Adapter | Layer::Config => (None, None),
+        }
+    }
+}
+
+/// What the project is, chosen with `rvy new project --template`. It decides
+/// the starter files and which layers entities get.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectKind {
+    /// Axum REST API with OpenAPI docs
+    #[default]
+    Api,
+    /// Background worker running a job loop
+    Worker,
+    /// Command-line tool built on clap
+    Cli,
+    /// Library crate without a binary
+    Lib,
+    /// Binary with only the domain layers and no database code
+    Minimal,
+}
+
+impl ProjectKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ProjectKind::Api => "api",
+            ProjectKind::Worker => "worker",
+            ProjectKind::Cli => "cli",
+            ProjectKind::Lib => "lib",
+            ProjectKind::Minimal => "minimal",
+        }
+    }
+
+    /// Whether the project serves HTTP: handlers, Swagger UI, integration
+    /// tests against the router and the runnable example.
+    pub fn http(self) -> bool {
+        self == ProjectKind::Api
+    }
+
+    /// Whether entities of this kind of project get `layer`.
+    pub fn has(self, layer: Layer) -> bool {
+        match self {
+            ProjectKind::Api => true,
+            ProjectKind::Worker | ProjectKind::Cli | ProjectKind::Lib => layer != Layer::Handler,
+            ProjectKind::Minimal => matches!(layer, Layer::Service | Layer::Usecase | Layer::Repository | Layer::Data),
+        }
+    }
+
+    pub fn layers(self) -> Vec<Layer> {
+        Layer::ALL.into_iter().filter(|layer| self.has(*layer)).collect()
+    }
+}
+
+/// HTTP framework of an api project, chosen with `rvy new project --web`.
+/// Only handlers, the server in main.rs and integration tests depend on it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WebFramework {
+    /// Axum 0.7
+    #[default]
+    Axum,
+    /// actix-web 4
+    Actix,
+}
+
+impl WebFramework {
+    pub fn name(self) -> &'static str {
+        match self {
+            WebFramework::Axum => "axum",
+            WebFramework::Actix => "actix",
+        }
+    }
+
+    /// Template rendered for `path`: actix has its own copies under `actix/`.
+    pub fn template(self, path: &str) -> String {
+        match self {
+            WebFramework::Axum => path.to_string(),
+            WebFramework::Actix => format!("actix/{}", path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayerDef {
+    dir: Option<String>,
+    file_suffix: Option<String>,
+    type_suffix: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ServerDef {
+    port: Option<u16>,
+    grpc_port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PathsDef {
+    migrations: Option<String>,
+    tests: Option<String>,
+    examples: Option<String>,
+    docs: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    kind: Option<ProjectKind>,
+    web: Option<WebFramework>,
+    databases: Option<Vec<String>>,
+    mod_marker: Option<String>,
+    server: Option<ServerDef>,
+    #[serde(default)]
+    paths: PathsDef,
+    #[serde(default)]
+    layers: BTreeMap<Layer, LayerDef>,
+}
+
+/// Where and how one layer's files are generated.
+#[derive(Debug, Clone)]
+pub struct LayerConfig {
+    pub dir: String,
+    pub file_suffix: String,
+    pub type_suffix: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectConfig {
+    pub kind: ProjectKind,
+    pub web: WebFramework,
+    pub databases: Vec<String>,
+    pub mod_marker: String,
+    pub port: u16,
+    pub grpc_port: u16,
+    pub migrations_dir: String,
+    pub tests_dir: String,
+    pub examples_dir: String,
+    pub docs_dir: String,
+    layers: BTreeMap<Layer, LayerConfig>,
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        Self::from_file(ConfigFile::default()).expect("default config is valid")
+    }
+}
+
+impl ProjectConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let file: ConfigFile =
+            toml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+        Self::from_file(file).map_err(|e| format!("invalid {}: {}", path.display(), e))
+    }
+
+    /// Load `rvy.toml` from the current directory, or the defaults without one.
+    pub fn load_default() -> Result<Self, String> {
+        let path = Path::new(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load(path)
+    }
+
+    fn from_file(file: ConfigFile) -> Result<Self, String> {
+        let databases = match file.databases {
+            Some(databases) => parse_databases(&databases).map_err(|e| format!("databases: {}", e))?,
+            None => DATABASES.iter().map(|db| db.to_string()).collect(),
+        };
+
+        let kind = file.kind.unwrap_or_default();
+        if file.web.is_some() && !kind.http() {
+            return Err(format!("web: {} projects have no HTTP server", kind.name()));
+        }
+
+        let mut layers = BTreeMap::new();
+        for layer in Layer::ALL {
+            let def = file.layers.get(&layer).cloned().unwrap_or_default();
+            layers.insert(layer, resolve_layer(layer, def)?);
+        }
+
+        let paths = file.paths;
+        Ok(Self {
+            kind,
+            web: file.web.unwrap_or_default(),
+            databases,
+            mod_marker: file.mod_marker.unwrap_or_else(|| DEFAULT_MOD_MARKER.to_string()),
+            port: file.server.as_ref().and_then(|server| server.port).unwrap_or(3000),
+            grpc_port: file.server.as_ref().and_then(|server| server.grpc_port).unwrap_or(50051),
+            migrations_dir: dir_path("paths.migrations", paths.migrations, "migrations")?,
+            tests_dir: dir_path("paths.tests", paths.tests, "tests")?,
+            examples_dir: dir_path("paths.examples", paths.examples, "examples")?,
+            docs_dir: dir_path("paths.docs", paths.docs, "docs")?,
+            layers,
+        })
+    }
+
+    /// Databases generated code talks to: none in a project without adapters.
+    pub fn code_databases(&self) -> &[String] {
+        if self.kind.has(Layer::Adapter) {
+            &self.databases
+        } else {
+            &[]
+        }
+    }
+
+    pub fn layer(&self, layer: Layer) -> &LayerConfig {
+        &self.layers[&layer]
+    }
+
+    /// Module name of an entity's file in `layer`, e.g. `user_service`.
+    pub fn module_name(&self, layer: Layer, name: &str) -> String {
+        format!("{}{}", name, self.layer(layer).file_suffix)
+    }
+
+    /// Path of an entity's file in `layer`, e.g. `src/service/user_service.rs`.
+    pub fn file_path(&self, layer: Layer, name: &str) -> String {
+        format!("{}/{}.rs", self.layer(layer).dir, self.module_name(layer, name))
+    }
+
+    /// `mod.rs` that declares the modules of `layer`.
+    pub fn mod_file(&self, layer: Layer) -> String {
+        format!("{}/mod.rs", self.layer(layer).dir)
+    }
+
+    /// Module path of `layer` relative to the crate root, e.g. `domain::service`.
+    pub fn module_path(&self, layer: Layer) -> String {
+        let dir = &self.layer(layer).dir;
+        dir.strip_prefix("src/").unwrap_or(dir).replace('/', "::")
+    }
+
+    /// Type name of an entity in `layer`, e.g. `UserService`.
+    pub fn type_name(&self, layer: Layer, pascal: &str) -> String {
+        format!("{}{}", pascal, self.layer(layer).type_suffix)
+    }
+}
+
+fn resolve_layer(layer: Layer, def: LayerDef) -> Result<LayerConfig, String> {
+    let key = layer.key();
+    let dir = match def.dir {
+        Some(dir) => {
+            let dir = dir.trim_end_matches('/').to_string();
+            let module = dir.strip_prefix("src/").unwrap_or_default();
+            let valid = !module.is_empty()
+                && module
+                    .split('/')
+                    .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+            if !valid {
+                return Err(format!(
+                    "layers.{}.dir must be a module directory under src/, got '{}'",
+                    key, dir
+                ));
+            }
+            dir
+        }
+        None => format!("src/{}", key),
+    };
+
+    let (default_file, default_type) = layer.default_suffixes();
+    let file_suffix = suffix(key, "file_suffix", def.file_suffix, default_file)?;
+    if !file_suffix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("layers.{}.file_suffix '{}' is not a valid module suffix", key, file_suffix));
+    }
+    let type_suffix = suffix(key, "type_suffix", def.type_suffix, default_type)?;
+    if !type_suffix.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("layers.{}.type_suffix '{}' is not a valid type suffix", key, type_suffix));
+    }
+
+    Ok(LayerConfig { dir, file_suffix, type_suffix })
+}
+
+fn suffix(layer: &str, key: &str, value: Option<String>, default: Option<&str>) -> Result<String, String> {
+    match (value, default) {
+        (Some(_), None) => Err(format!("layers.{} does not support `{}`", layer, key)),
+        (Some(value), Some(_)) => Ok(value),
+        (None, default) => Ok(default.unwrap_or_default().to_string()),
+    }
+}
+
+fn dir_path(key: &str, value: Option<String>, default: &str) -> Result<String, String> {
+    match value {
+        Some(dir) if dir.trim().is_empty() => Err(format!("{} must not be empty", key)),
+        Some(dir) => Ok(dir.trim_end_matches('/').to_string()),
+        None => Ok(default.to_string()),
+    }
+}
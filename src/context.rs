@@ -1,6 +1,30 @@
+use crate::config::ProjectConfig;
+use crate::generator::field::Field;
+use crate::generator::naming::Naming;
+use crate::report::Reporter;
+use crate::schema::Permissions;
+use crate::vfs::FileSystem;
+use std::rc::Rc;
+
 #[derive(Debug, Clone)]
 pub struct Context {
     pub dry_run: bool,
     pub force: bool,
+    pub force_modified: bool,  // Let --force replace files edited since they were generated
+    pub merge: bool,  // Three-way merge into existing files (rvy regen) instead of skipping them
     pub is_new_all: bool,  // Flag to indicate if called from new-all command
+    pub fields: Vec<Field>,  // Entity fields from --field (defaults to `name: String`)
+    pub databases: Vec<String>,  // Databases the entity targets (defaults to all)
+    pub plural: Option<String>,  // Plural override from rvy.entities.toml
+    pub table: Option<String>,  // Table/collection name override from rvy.entities.toml
+    pub permissions: Permissions,  // Roles or scopes per CRUD operation from rvy.entities.toml
+    pub config: ProjectConfig,  // Layer directories, suffixes and defaults from rvy.toml
+    pub fs: Rc<dyn FileSystem>,  // Where generated files are read and written (disk, or memory for --dry-run)
+    pub report: Rc<Reporter>,  // Where files touched, warnings and tips are reported (text or --output json)
+}
+
+impl Context {
+    pub fn naming(&self, name: &str) -> Naming {
+        Naming::new(name, self.plural.as_deref(), self.table.as_deref())
+    }
 }
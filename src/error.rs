@@ -0,0 +1,89 @@
+// Errors of the rvy CLI itself, each class with its own exit code so scripts
+// can tell why a command failed.
+
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum RvyError {
+    /// Invalid arguments: unknown database, bad field, missing entity...
+    Usage(String),
+    /// rvy.toml or rvy.entities.toml is invalid
+    Config(String),
+    /// A template failed to render
+    Template(String),
+    /// An existing source file (main.rs, mod.rs, ...) could not be edited
+    Source { path: String, message: String },
+    /// Files edited since they were generated stand in the way
+    Modified(Vec<String>),
+    /// .rvy/manifest.json or a base copy is unreadable or can't be written
+    Manifest(String),
+    /// Reading or writing a project file failed
+    Io { path: String, source: io::Error },
+    /// A failure inside the named generation step, e.g. `product › handler`
+    Step { step: String, source: Box<RvyError> },
+}
+
+impl RvyError {
+    pub fn edit(path: &str, message: impl Into<String>) -> Self {
+        RvyError::Source { path: path.to_string(), message: message.into() }
+    }
+
+    pub fn io(path: impl fmt::Display, source: io::Error) -> Self {
+        RvyError::Io { path: path.to_string(), source }
+    }
+
+    /// Mark the error as raised inside `step`; outer steps come first.
+    pub fn in_step(self, step: &str) -> Self {
+        match self {
+            RvyError::Step { step: inner, source } => RvyError::Step { step: format!("{} › {}", step, inner), source },
+            other => RvyError::Step { step: step.to_string(), source: Box::new(other) },
+        }
+    }
+
+    /// Process exit code for the error class. 1 is left to panics and
+    /// anything unexpected, 2 matches clap's own usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RvyError::Usage(_) => 2,
+            RvyError::Config(_) => 3,
+            RvyError::Template(_) => 4,
+            RvyError::Source { .. } => 5,
+            RvyError::Modified(_) => 6,
+            RvyError::Manifest(_) => 7,
+            RvyError::Io { .. } => 8,
+            RvyError::Step { source, .. } => source.exit_code(),
+        }
+    }
+}
+
+impl fmt::Display for RvyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RvyError::Usage(message) => write!(f, "{}", message),
+            RvyError::Config(message) => write!(f, "Config error: {}", message),
+            RvyError::Template(message) => write!(f, "Template error: {}", message),
+            RvyError::Source { path, message } => write!(f, "Cannot update {}: {}", path, message),
+            RvyError::Modified(files) => {
+                writeln!(f, "Modified since they were generated:")?;
+                for file in files {
+                    writeln!(f, "   {}", file)?;
+                }
+                write!(f, "Use --force-modified to go ahead anyway")
+            }
+            RvyError::Manifest(message) => write!(f, "Manifest error: {}", message),
+            RvyError::Io { path, source } => write!(f, "File error: {}: {}", path, source),
+            RvyError::Step { step, source } => write!(f, "{}\n   Failed at: {}", source, step),
+        }
+    }
+}
+
+impl std::error::Error for RvyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RvyError::Io { source, .. } => Some(source),
+            RvyError::Step { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
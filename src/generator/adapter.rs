@@ -1,95 +1,86 @@
+use crate::config::{Layer, WebFramework};
 use crate::context::Context;
-use crate::generator::{render, write_file, update_module_exports};
+use crate::error::RvyError;
+use crate::generator::cargo::{self, crate_name, dep};
+use crate::generator::{render, write_generated, write_rendered, update_module_exports};
 
-pub fn generate(ctx: &Context, name: &str, db_type: &str) {
+pub fn generate(ctx: &Context, name: &str, db_type: &str) -> Result<(), RvyError> {
     let template = match db_type.to_lowercase().as_str() {
-        "postgres" => include_str!("../../templates/adapter/postgres.rs.tpl"),
-        "mysql" => include_str!("../../templates/adapter/mysql.rs.tpl"),
-        "mongodb" => include_str!("../../templates/adapter/mongodb.rs.tpl"),
-        "sqlite" => include_str!("../../templates/adapter/sqlite.rs.tpl"),
+        "postgres" => "adapter/postgres.rs.tpl",
+        "mysql" => "adapter/mysql.rs.tpl",
+        "mongodb" => "adapter/mongodb.rs.tpl",
+        "sqlite" => "adapter/sqlite.rs.tpl",
         _ => {
-            eprintln!("Error: Unsupported database type '{}'", db_type);
-            eprintln!("Supported types: postgres, mysql, mongodb, sqlite");
-            return;
+            return Err(RvyError::Usage(format!(
+                "Unsupported database type '{}' (supported: postgres, mysql, mongodb, sqlite)",
+                db_type
+            )));
         }
     };
 
-    let content = render(template, name);
+    let config = &ctx.config;
+    let module_name = format!("{}_{}", name, db_type.to_lowercase());
+    let path = format!("{}/{}.rs", config.layer(Layer::Adapter).dir, module_name);
 
-    let filename = format!("{}_{}.rs", name, db_type.to_lowercase());
-    let path = format!("src/adapter/{}", filename);
-
-    write_file(ctx, &path, &content);
-    update_module_exports(ctx, "src/adapter/mod.rs", &format!("{}_{}", name, db_type.to_lowercase()));
+    let mut deps = vec![dep("async-trait", "0.1")];
+    deps.extend(cargo::database_crates(&[db_type.to_lowercase()]));
+    deps.extend(cargo::field_crates(ctx));
+    cargo::require(ctx, &deps)?;
+    write_rendered(ctx, &path, template, name)?;
+    update_module_exports(ctx, &config.mod_file(Layer::Adapter), &module_name)
 }
 
-pub fn generate_all(ctx: &Context, name: &str) {
-    let db_types = ["postgres", "mysql", "mongodb", "sqlite"];
-    
-    for db_type in &db_types {
-        generate(ctx, name, db_type);
+pub fn generate_all(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    for db_type in &ctx.databases {
+        generate(ctx, name, db_type)?;
     }
+    Ok(())
 }
 
-pub fn generate_config(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/config/database.rs.tpl");
-    let content = render(template, name);
-    let path = "src/config/database.rs";
+pub fn generate_config(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let config = &ctx.config;
+    let path = format!("{}/database.rs", config.layer(Layer::Config).dir);
     
-    write_file(ctx, path, &content);
+    let mut deps = vec![dep("serde", "1.0").features(&["derive"])];
+    deps.extend(cargo::database_crates(&config.databases));
+    cargo::require(ctx, &deps)?;
+    write_rendered(ctx, &path, "config/database.rs.tpl", name)?;
     
     // Update mod.rs to export database
-    update_module_exports(ctx, "src/config/mod.rs", "database");
+    update_module_exports(ctx, &config.mod_file(Layer::Config), "database")
 }
 
-pub fn generate_factory(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/factory/repository_factory.rs.tpl");
-    let content = render(template, name);
-    let path = format!("src/factory/{}_factory.rs", name);
+pub fn generate_factory(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let config = &ctx.config;
+    let path = config.file_path(Layer::Factory, name);
     
-    write_file(ctx, &path, &content);
+    cargo::require(ctx, &cargo::database_crates(&config.databases))?;
+    write_rendered(ctx, &path, "factory/repository_factory.rs.tpl", name)?;
     
     // Update mod.rs to export this factory
-    let module_name = format!("{}_factory", name);
-    update_module_exports(ctx, "src/factory/mod.rs", &module_name);
+    update_module_exports(ctx, &config.mod_file(Layer::Factory), &config.module_name(Layer::Factory, name))
 }
 
-pub fn generate_usage_docs(ctx: &Context, name: &str) {
+pub fn generate_usage_docs(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let mut deps = vec![cargo::web_crate(ctx.config.web)];
+    if ctx.config.web == WebFramework::Axum {
+        deps.push(dep("tokio", "1").features(&["full"]));
+    }
+    cargo::require(ctx, &deps)?;
+
     // Generate example main
-    let main_template = include_str!("../../templates/examples/main_with_crud.rs.tpl");
-    let mut main_content = render(main_template, name);
+    let template = "examples/main_with_crud.rs.tpl";
+    let mut main_content = render(ctx, template, name)?;
     
     // Replace crate name with actual package name from Cargo.toml
-    if let Some(package_name) = get_package_name() {
-        let crate_name = package_name.replace('-', "_");
+    if let Some(crate_name) = crate_name(ctx) {
         main_content = main_content.replace("CRATE_NAME", &crate_name);
     }
     
-    let main_path = format!("examples/{}_example.rs", name);
-    write_file(ctx, &main_path, &main_content);
+    let main_path = format!("{}/{}_example.rs", ctx.config.examples_dir, name);
+    write_generated(ctx, &main_path, &main_content, Some(template))?;
 
     // Generate usage documentation
-    let doc_template = include_str!("../../templates/examples/USAGE.md.tpl");
-    let doc_content = render(doc_template, name);
-    let doc_path = format!("docs/{}_USAGE.md", name);
-    write_file(ctx, &doc_path, &doc_content);
-}
-
-fn get_package_name() -> Option<String> {
-    use std::path::Path;
-    let cargo_path = "Cargo.toml";
-    if !Path::new(cargo_path).exists() {
-        return None;
-    }
-    
-    let content = std::fs::read_to_string(cargo_path).ok()?;
-    for line in content.lines() {
-        if line.trim().starts_with("name") {
-            if let Some(name) = line.split('=').nth(1) {
-                let name = name.trim().trim_matches('"').trim();
-                return Some(name.to_string());
-            }
-        }
-    }
-    None
+    let doc_path = format!("{}/{}_USAGE.md", ctx.config.docs_dir, name);
+    write_rendered(ctx, &doc_path, "examples/USAGE.md.tpl", name)
 }
@@ -0,0 +1,40 @@
+// JWT bearer authentication shared by all handlers: `src/auth.rs`, written
+// with the first handler, and its tests.
+
+use std::path::Path;
+
+use crate::context::Context;
+use crate::error::RvyError;
+use crate::generator::cargo::{self, crate_name, dep};
+use crate::generator::{declare_modules, manifest, render, write_generated};
+
+const AUTH_PATH: &str = "src/auth.rs";
+
+pub fn generate_auth_module(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    if ctx.fs.exists(Path::new(AUTH_PATH)) {
+        return Ok(());
+    }
+
+    cargo::require(ctx, &[
+        dep("jsonwebtoken", "9"),
+        dep("serde", "1.0").features(&["derive"]),
+        dep("chrono", "0.4").features(&["serde"]),
+    ])?;
+
+    let template = "auth.rs.tpl";
+    let content = render(ctx, template, name)?;
+    ctx.fs.write(Path::new(AUTH_PATH), &content).map_err(|e| RvyError::io(AUTH_PATH, e))?;
+    manifest::record(&*ctx.fs, AUTH_PATH, &content, Some(template))?;
+    ctx.report.created(AUTH_PATH);
+
+    declare_modules(ctx)?;
+    generate_test(ctx, name)
+}
+
+fn generate_test(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let template = "tests/auth_test.rs.tpl";
+    let path = format!("{}/auth_test.rs", ctx.config.tests_dir);
+    let crate_name = crate_name(ctx).unwrap_or_else(|| name.to_string());
+    let content = render(ctx, template, name)?.replace("crate::", &format!("{}::", crate_name));
+    write_generated(ctx, &path, &content, Some(template))
+}
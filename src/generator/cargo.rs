@@ -0,0 +1,409 @@
+// Cargo manifests: the crate name generated code imports the project by,
+// adding a new project to the workspace around it, and adding the crates
+// generated code needs.
+//
+// Manifests are edited with toml_edit so comments and formatting survive.
+
+use std::path::{Component, Path, PathBuf};
+
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table, Value};
+
+use crate::config::WebFramework;
+use crate::context::Context;
+use crate::error::RvyError;
+use crate::generator::field::needs_crate;
+use crate::generator::manifest;
+
+pub const MANIFEST: &str = "Cargo.toml";
+
+/// Name the project's library is imported by from tests and examples: the
+/// `[lib] name` in Cargo.toml, or the package name with `-` as `_`.
+pub fn crate_name(ctx: &Context) -> Option<String> {
+    let manifest: toml::Table = ctx.fs.read(Path::new(MANIFEST)).ok()?.parse().ok()?;
+    let name = |section: &str| manifest.get(section)?.get("name")?.as_str().map(str::to_string);
+    name("lib").or_else(|| name("package")).map(|name| name.replace('-', "_"))
+}
+
+/// Members of a virtual workspace manifest (one without a `[package]`), or
+/// `None` for a crate's manifest.
+pub fn virtual_members(content: &str) -> Option<Vec<String>> {
+    let manifest: toml::Table = content.parse().ok()?;
+    if manifest.contains_key("package") {
+        return None;
+    }
+    let members = manifest.get("workspace")?.get("members").and_then(|m| m.as_array()).cloned().unwrap_or_default();
+    Some(members.iter().filter_map(|m| m.as_str().map(str::to_string)).collect())
+}
+
+/// The workspace a crate created at `dir` (relative to the current
+/// directory) falls in.
+pub struct Workspace {
+    /// Its Cargo.toml, relative to the current directory
+    pub manifest_path: String,
+    /// Path of the new crate relative to the workspace root, as in `members`
+    pub member: String,
+    document: DocumentMut,
+}
+
+impl Workspace {
+    /// The nearest workspace manifest in the current directory or above it.
+    pub fn around(ctx: &Context, dir: &str) -> Result<Option<Self>, RvyError> {
+        let cwd = std::env::current_dir().map_err(|e| RvyError::io(".", e))?;
+        for (depth, root) in cwd.ancestors().enumerate() {
+            let manifest_path = format!("{}{}", "../".repeat(depth), MANIFEST);
+            if !ctx.fs.exists(Path::new(&manifest_path)) {
+                continue;
+            }
+            let content = ctx.fs.read(Path::new(&manifest_path)).map_err(|e| RvyError::io(&manifest_path, e))?;
+            let document: DocumentMut = content
+                .parse()
+                .map_err(|e| RvyError::Config(format!("invalid {}: {}", manifest_path, e)))?;
+            if !document.contains_key("workspace") {
+                continue;
+            }
+            let relative = cwd.strip_prefix(root).unwrap_or(&cwd).join(dir);
+            let member = relative
+                .components()
+                .filter_map(|c| match c {
+                    Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            return Ok(Some(Self { manifest_path, member, document }));
+        }
+        Ok(None)
+    }
+
+    fn table(&self, key: &str) -> Option<&toml_edit::Table> {
+        self.document.get("workspace")?.get(key)?.as_table()
+    }
+
+    /// Whether `members` already covers the new crate, e.g. through `crates/*`.
+    pub fn includes_member(&self) -> bool {
+        let patterns = |key: &str| -> Vec<String> {
+            self.document
+                .get("workspace")
+                .and_then(|w| w.get(key))
+                .and_then(Item::as_array)
+                .map(|a| a.iter().filter_map(|m| m.as_str().map(str::to_string)).collect())
+                .unwrap_or_default()
+        };
+        let excluded = patterns("exclude").iter().any(|p| glob_match(p, &self.member));
+        !excluded && patterns("members").iter().any(|p| glob_match(p, &self.member))
+    }
+
+    /// Add the new crate to `[workspace] members`. Returns the updated manifest.
+    pub fn add_member(&mut self) -> String {
+        let workspace = self.document["workspace"].as_table_like_mut().expect("checked in `around`");
+        let members = workspace.entry("members").or_insert(value(Array::new()));
+        if let Some(members) = members.as_array_mut() {
+            // One member per line stays one member per line
+            let prefix = members.get(0).and_then(|m| m.decor().prefix()).and_then(|p| p.as_str()).map(str::to_string);
+            members.push(self.member.as_str());
+            if let Some(prefix) = prefix.filter(|p| p.contains('\n')) {
+                let last = members.len() - 1;
+                members.get_mut(last).expect("just pushed").decor_mut().set_prefix(prefix);
+            }
+        }
+        self.document.to_string()
+    }
+
+    /// Rewrite the new crate's manifest to inherit from the workspace:
+    /// dependencies declared in `[workspace.dependencies]` become
+    /// `workspace = true` (keeping extra features), and so do the package
+    /// fields set in `[workspace.package]`.
+    pub fn inherit(&self, crate_manifest: &str) -> Result<String, RvyError> {
+        let mut document: DocumentMut = crate_manifest
+            .parse()
+            .map_err(|e| RvyError::Template(format!("generated Cargo.toml is invalid: {}", e)))?;
+
+        if let (Some(shared), Some(package)) = (self.table("package"), document.get_mut("package").and_then(Item::as_table_mut)) {
+            for key in ["version", "edition", "license", "authors", "rust-version"] {
+                if shared.contains_key(key) && package.contains_key(key) {
+                    // `version.workspace = true`
+                    let mut inherited = Table::new();
+                    inherited.set_dotted(true);
+                    inherited.insert("workspace", value(true));
+                    package[key] = Item::Table(inherited);
+                }
+            }
+        }
+
+        if let Some(shared) = self.table("dependencies") {
+            for section in ["dependencies", "dev-dependencies", "build-dependencies"] {
+                let Some(deps) = document.get_mut(section).and_then(Item::as_table_mut) else {
+                    continue;
+                };
+                for (name, dep) in deps.iter_mut() {
+                    if shared.contains_key(&name) {
+                        *dep = value(inherited_dependency(dep));
+                    }
+                }
+            }
+        }
+        Ok(document.to_string())
+    }
+}
+
+// `{ workspace = true }` plus the features the crate asked for
+fn inherited_dependency(dep: &Item) -> InlineTable {
+    let mut inherited = InlineTable::new();
+    inherited.insert("workspace", true.into());
+    let features = dep.as_table_like().and_then(|t| t.get("features")).and_then(Item::as_value).cloned();
+    if let Some(Value::Array(features)) = features {
+        let mut features = features;
+        features.decor_mut().clear();
+        inherited.insert("features", Value::Array(features));
+    }
+    inherited
+}
+
+// Cargo's member globs: `*` within a path component, `**`-free
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: PathBuf = pattern.trim_end_matches('/').into();
+    let path = Path::new(path);
+    let (pattern, path): (Vec<_>, Vec<_>) = (pattern.components().collect(), path.components().collect());
+    pattern.len() == path.len()
+        && pattern.iter().zip(&path).all(|(p, c)| wildcard(&p.as_os_str().to_string_lossy(), &c.as_os_str().to_string_lossy()))
+}
+
+fn wildcard(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=text.len()).filter(|&i| text.is_char_boundary(i)).any(|i| wildcard(rest, &text[i..]))
+        }
+    }
+}
+
+/// A crate generated code uses, as it would be written in Cargo.toml.
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    name: &'static str,
+    version: &'static str,
+    features: Vec<&'static str>,
+    kind: DepKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DepKind {
+    Normal,
+    Dev,
+    Build,
+}
+
+pub fn dep(name: &'static str, version: &'static str) -> Dependency {
+    Dependency { name, version, features: Vec::new(), kind: DepKind::Normal }
+}
+
+impl Dependency {
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn features(mut self, features: &[&'static str]) -> Self {
+        self.features.extend_from_slice(features);
+        self
+    }
+
+    /// Only needed by tests: goes to `[dev-dependencies]`.
+    pub fn dev(mut self) -> Self {
+        self.kind = DepKind::Dev;
+        self
+    }
+
+    /// Only needed by build.rs: goes to `[build-dependencies]`.
+    pub fn build(mut self) -> Self {
+        self.kind = DepKind::Build;
+        self
+    }
+
+    // Sections in which the crate already counts as present; it is added
+    // to the last one
+    fn sections(&self) -> &'static [&'static str] {
+        match self.kind {
+            DepKind::Normal => &["dependencies"],
+            DepKind::Dev => &["dependencies", "dev-dependencies"],
+            DepKind::Build => &["build-dependencies"],
+        }
+    }
+}
+
+/// The crate of the project's HTTP framework.
+pub fn web_crate(web: WebFramework) -> Dependency {
+    match web {
+        WebFramework::Axum => dep("axum", "0.7"),
+        WebFramework::Actix => dep("actix-web", "4"),
+    }
+}
+
+/// The crates the code for `databases` needs: sqlx with a feature per SQL
+/// database, and mongodb with futures for its cursors.
+pub fn database_crates(databases: &[String]) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let sql: Vec<&'static str> = ["postgres", "mysql", "sqlite"]
+        .into_iter()
+        .filter(|db| databases.iter().any(|d| d == db))
+        .collect();
+    if !sql.is_empty() {
+        deps.push(dep("sqlx", "0.8").features(&["runtime-tokio-rustls", "chrono"]).features(&sql));
+    }
+    if databases.iter().any(|db| db == "mongodb") {
+        deps.push(dep("mongodb", "3.1"));
+        deps.push(dep("futures", "0.3"));
+    }
+    deps
+}
+
+/// The crates behind the entity's field types, with the sqlx and utoipa
+/// features that map them.
+pub fn field_crates(ctx: &Context) -> Vec<Dependency> {
+    let has_sql = ctx.config.code_databases().iter().any(|db| db != "mongodb");
+    let mut deps = Vec::new();
+    for (krate, version, features, feature) in [
+        ("rust_decimal", "1", &["serde"][..], "decimal"),
+        ("uuid", "1", &["serde", "v4"][..], "uuid"),
+    ] {
+        if !needs_crate(&ctx.fields, krate) {
+            continue;
+        }
+        deps.push(dep(krate, version).features(features));
+        if ctx.config.kind.http() {
+            deps.push(dep("utoipa", "5").features(&[feature]));
+        }
+        if has_sql {
+            deps.push(dep("sqlx", "0.8").features(&[krate]));
+        }
+    }
+    deps
+}
+
+/// Add the crates generated code needs to the project's Cargo.toml, and the
+/// features it needs to crates already there. Versions already present are
+/// left alone, with a warning when they are older than the generated code
+/// expects. Without a Cargo.toml there is nothing to do.
+pub fn require(ctx: &Context, deps: &[Dependency]) -> Result<(), RvyError> {
+    let path = Path::new(MANIFEST);
+    if !ctx.fs.exists(path) {
+        return Ok(());
+    }
+    let original = ctx.fs.read(path).map_err(|e| RvyError::io(MANIFEST, e))?;
+    let mut document: DocumentMut = original
+        .parse()
+        .map_err(|e| RvyError::Config(format!("invalid {}: {}", MANIFEST, e)))?;
+    let workspace = Workspace::around(ctx, ".")?;
+    let shared = workspace.as_ref().and_then(|w| w.table("dependencies"));
+
+    let mut added = Vec::new();
+    for dep in deps {
+        let sections = dep.sections();
+        let present = sections
+            .iter()
+            .copied()
+            .find(|section| document.get(section).and_then(|s| s.get(dep.name)).is_some());
+        let Some(section) = present else {
+            let section = sections[sections.len() - 1];
+            let table = document.entry(section).or_insert(toml_edit::table());
+            let Some(table) = table.as_table_like_mut() else {
+                return Err(RvyError::Config(format!("[{}] in {} is not a table", section, MANIFEST)));
+            };
+            let entry = if shared.is_some_and(|s| s.contains_key(dep.name)) {
+                value(inherited_dependency(&dep.entry()))
+            } else {
+                dep.entry()
+            };
+            table.insert(dep.name, entry);
+            added.push(format!("{} {}", dep.name, dep.version));
+            continue;
+        };
+
+        let entry = &mut document[section][dep.name];
+        if let Some(version) = version_of(entry).filter(|v| older(v, dep.version)) {
+            let message = format!("{} {} is older than the {} generated code is written for", dep.name, version, dep.version);
+            ctx.report.warning(Some(MANIFEST), &message);
+        }
+        let missing = add_features(entry, &dep.features);
+        if !missing.is_empty() {
+            added.push(format!("{} features {}", dep.name, missing.join(", ")));
+        }
+    }
+
+    if added.is_empty() {
+        return Ok(());
+    }
+    let content = document.to_string();
+    ctx.fs.write(path, &content).map_err(|e| RvyError::io(MANIFEST, e))?;
+    manifest::refresh(&*ctx.fs, MANIFEST, &original, &content)?;
+    ctx.report.modified(MANIFEST, Some(&format!("added {}", added.join("; "))));
+    Ok(())
+}
+
+impl Dependency {
+    // `name = "1"`, or `name = { version = "1", features = [...] }`
+    fn entry(&self) -> Item {
+        if self.features.is_empty() {
+            return value(self.version);
+        }
+        let mut entry = InlineTable::new();
+        entry.insert("version", self.version.into());
+        entry.insert("features", Value::Array(self.features.iter().copied().collect()));
+        value(entry)
+    }
+}
+
+// The version requirement of a dependency entry, unless it comes from the
+// workspace, a path or git
+fn version_of(entry: &Item) -> Option<String> {
+    match entry.as_value() {
+        Some(Value::String(version)) => Some(version.value().clone()),
+        _ => entry.as_table_like()?.get("version")?.as_str().map(str::to_string),
+    }
+}
+
+// Whether the requirement `present` allows only versions below `wanted`,
+// comparing the numbers of `1.40`, `^0.7`, `=0.8.1`...
+fn older(present: &str, wanted: &str) -> bool {
+    let numbers = |version: &str| -> Vec<u64> {
+        version
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .split('.')
+            .map_while(|part| part.parse().ok())
+            .collect()
+    };
+    let (present, wanted) = (numbers(present), numbers(wanted));
+    // Compare as far as both are given: `1` is not older than `1.40`
+    present.iter().zip(&wanted).find(|(p, w)| p != w).is_some_and(|(p, w)| p < w)
+}
+
+// Add the features `entry` lacks, turning `name = "1"` into a table if need
+// be. Returns the features added.
+fn add_features(entry: &mut Item, features: &[&'static str]) -> Vec<&'static str> {
+    let current: Vec<String> = entry
+        .as_table_like()
+        .and_then(|t| t.get("features"))
+        .and_then(Item::as_array)
+        .map(|a| a.iter().filter_map(|f| f.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    let missing: Vec<&'static str> = features.iter().copied().filter(|f| !current.iter().any(|c| c == f)).collect();
+    if missing.is_empty() {
+        return missing;
+    }
+
+    if let Some(Value::String(version)) = entry.as_value() {
+        let mut table = InlineTable::new();
+        table.insert("version", version.value().as_str().into());
+        *entry = value(table);
+    }
+    let Some(table) = entry.as_table_like_mut() else {
+        return Vec::new();
+    };
+    let features = table.entry("features").or_insert(value(Array::new()));
+    if let Some(features) = features.as_array_mut() {
+        features.extend(missing.iter().copied());
+    }
+    missing
+}
@@ -1,23 +1,32 @@
+use crate::config::Layer;
 use crate::context::Context;
-use crate::generator::{render, write_file, update_module_exports};
+use crate::error::RvyError;
+use crate::generator::{write_rendered, update_module_exports};
+use crate::generator::cargo::{self, dep};
 use std::path::Path;
 
-pub fn generate(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/data.rs.tpl");
+pub fn generate(ctx: &Context, name: &str) -> Result<(), RvyError> {
 
-    let content = render(template, name);
+    let config = &ctx.config;
+    let path = config.file_path(Layer::Data, name);
+    let handler_path = config.file_path(Layer::Handler, name);
 
-    let path = format!("src/data/{}_data.rs", name);
-    let handler_path = format!("src/handler/{}_handler.rs", name);
+    let mut deps = vec![dep("serde", "1.0").features(&["derive"]), dep("chrono", "0.4").features(&["serde"])];
+    if config.kind.http() {
+        deps.push(dep("utoipa", "5").features(&["chrono"]));
+    }
+    // Rows are mapped with sqlx::FromRow
+    deps.extend(cargo::database_crates(config.code_databases()).into_iter().filter(|d| d.name() == "sqlx"));
+    // Field types backed by extra crates
+    deps.extend(cargo::field_crates(ctx));
+    cargo::require(ctx, &deps)?;
 
-    write_file(ctx, &path, &content);
-    update_module_exports(ctx, "src/data/mod.rs", &format!("{}_data", name));
+    write_rendered(ctx, &path, "data.rs.tpl", name)?;
+    update_module_exports(ctx, &config.mod_file(Layer::Data), &config.module_name(Layer::Data, name))?;
     
     // Check if handler exists and suggest regenerating swagger
-    if !ctx.dry_run && Path::new(&handler_path).exists() {
-        println!("💡 Tip: Data model updated. Regenerate Swagger docs with:");
-        println!("   rvy gen swagger {}", name);
-        println!("   or use --force to regenerate handler:");
-        println!("   rvy gen handler {} --force", name);
+    if !ctx.dry_run && !ctx.merge && ctx.fs.exists(Path::new(&handler_path)) {
+        ctx.report.tip(&format!("Data model updated. Bring the handler, adapters and Swagger docs up to date with `rvy regen {}`", name));
     }
+    Ok(())
 }
@@ -0,0 +1,93 @@
+// `rvy destroy <entity>`: remove the files gen-all generated for an entity
+// and undo its wiring in the mod.rs files, main.rs and tests/common.rs.
+
+use std::path::Path;
+
+use crate::config::Layer;
+use crate::context::Context;
+use crate::error::RvyError;
+use crate::generator::manifest::{self, FileState};
+use crate::generator::{file_state, graphql, grpc, handler, remove_module_export, test, DATABASES};
+
+pub fn destroy(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let config = &ctx.config;
+
+    let mut files = Vec::new();
+    let mut exports = Vec::new();
+    for layer in [Layer::Service, Layer::Usecase, Layer::Repository, Layer::Data, Layer::Handler, Layer::Factory] {
+        files.push(config.file_path(layer, name));
+        exports.push((config.mod_file(layer), config.module_name(layer, name)));
+    }
+    // Every database, not just the enabled ones: rvy.toml may have changed since
+    for db in DATABASES {
+        let module = format!("{}_{}", name, db);
+        files.push(format!("{}/{}.rs", config.layer(Layer::Adapter).dir, module));
+        exports.push((config.mod_file(Layer::Adapter), module));
+    }
+    files.extend(grpc::files(ctx, name));
+    exports.push(grpc::module(ctx, name));
+    files.extend(graphql::files(ctx, name));
+    exports.push(graphql::module(ctx, name));
+    files.push(format!("{}/{}_example.rs", config.examples_dir, name));
+    files.push(format!("{}/{}_USAGE.md", config.docs_dir, name));
+    files.push(format!("{}/{}_test.rs", config.tests_dir, name));
+    files.extend(migration_files(ctx, name));
+    files.retain(|file| ctx.fs.exists(Path::new(file)));
+
+    // Hand-edited files hold work that can't be regenerated
+    let mut modified = Vec::new();
+    for file in &files {
+        if file_state(ctx, file)? == FileState::Modified {
+            modified.push(file.clone());
+        }
+    }
+    if !modified.is_empty() && !ctx.force_modified {
+        return Err(RvyError::Modified(modified));
+    }
+
+    // Unwire first: main.rs is the edit most likely to fail, and nothing has
+    // been deleted yet if it does
+    let mut found = handler::remove_routes(ctx, name)?;
+    found |= grpc::remove_server(ctx, name)?;
+    found |= graphql::remove_schema(ctx, name)?;
+    for (mod_file, module) in &exports {
+        found |= remove_module_export(ctx, mod_file, module)?;
+    }
+    found |= test::remove_test_common(ctx, name)?;
+
+    for file in &files {
+        found = true;
+        ctx.fs.remove(Path::new(file)).map_err(|e| RvyError::io(file, e))?;
+        manifest::forget(&*ctx.fs, file)?;
+        ctx.report.deleted(file);
+    }
+
+    if !found {
+        return Err(RvyError::Usage(format!("Nothing generated for '{}' was found", name)));
+    }
+    manifest::forget_entity(&*ctx.fs, name)
+}
+
+// Migrations are named `<timestamp>_create_<table>_table_<db>.sql`, plus the
+// MongoDB `setup_<table>_collection.rs`
+fn migration_files(ctx: &Context, name: &str) -> Vec<String> {
+    let table = ctx.naming(name).table;
+    let setup = format!("setup_{}_collection.rs", table);
+    let Ok(entries) = ctx.fs.list(Path::new(&ctx.config.migrations_dir)) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<String> = entries
+        .into_iter()
+        .filter(|file| {
+            *file == setup
+                || DATABASES.iter().any(|db| {
+                    file.strip_suffix(&format!("_create_{}_table_{}.sql", table, db))
+                        .is_some_and(|timestamp| !timestamp.is_empty() && timestamp.chars().all(|c| c.is_ascii_digit()))
+                })
+        })
+        .map(|file| format!("{}/{}", ctx.config.migrations_dir, file))
+        .collect();
+    files.sort();
+    files
+}
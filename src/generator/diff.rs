@@ -0,0 +1,35 @@
+// Unified diffs of the changes a --dry-run would make
+
+use similar::TextDiff;
+
+use crate::generator::manifest::{BASE_DIR, MANIFEST_FILE};
+use crate::vfs::Change;
+
+/// Print one diff per changed file. A created file is diffed against
+/// /dev/null, and so is a removed one. rvy's own bookkeeping under `.rvy/`
+/// (the manifest and base copies) is left out.
+pub fn print(changes: &[Change]) {
+    for change in changes {
+        let bookkeeping = change.path.ends_with(MANIFEST_FILE) || change.path.ancestors().any(|dir| dir.ends_with(BASE_DIR));
+        if change.before == change.after || bookkeeping {
+            continue;
+        }
+        let path = change.path.to_string_lossy();
+        let (old, from) = match &change.before {
+            Some(content) => (content.as_str(), format!("a/{}", path)),
+            None => ("", "/dev/null".to_string()),
+        };
+        let (new, to) = match &change.after {
+            Some(content) => (content.as_str(), format!("b/{}", path)),
+            None => ("", "/dev/null".to_string()),
+        };
+        print!(
+            "{}",
+            TextDiff::from_lines(old, new)
+                .unified_diff()
+                .context_radius(3)
+                .header(&from, &to)
+                .missing_newline_hint(true)
+        );
+    }
+}
@@ -1,5 +1,9 @@
+use crate::config::Layer;
 use crate::context::Context;
-use crate::generator::{service, usecase, repository, data, adapter, handler, test, migration, error};
+use crate::error::RvyError;
+use crate::schema::Entity;
+use crate::generator::{service, usecase, repository, data, adapter, handler, grpc, graphql, test, migration, error, manifest, declare_modules};
+use crate::transaction::step;
 
 pub enum GenKind {
     Service,
@@ -7,6 +11,8 @@ pub enum GenKind {
     Repository,
     Data,
     Handler,
+    Grpc,
+    Graphql,
     Adapter(String), // database type: postgres, mysql, mongodb, sqlite
     AdapterAll,
     Config,
@@ -19,13 +25,68 @@ pub enum GenKind {
     MigrationAll,   // Migrations for all databases
 }
 
-pub fn dispatch(kind: GenKind, ctx: &Context, name: &str) {
+impl GenKind {
+    /// Name the generator's events are reported under.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GenKind::Service => "service",
+            GenKind::Usecase => "usecase",
+            GenKind::Repository => "repository",
+            GenKind::Data => "data",
+            GenKind::Handler => "handler",
+            GenKind::Grpc => "grpc",
+            GenKind::Graphql => "graphql",
+            GenKind::Adapter(_) | GenKind::AdapterAll => "adapter",
+            GenKind::Config => "config",
+            GenKind::Factory => "factory",
+            GenKind::Example => "example",
+            GenKind::Test | GenKind::AllTests => "test",
+            GenKind::IntegrationTest => "integration-test",
+            GenKind::Migration(_) | GenKind::MigrationAll => "migration",
+        }
+    }
+
+    /// Layer the generator needs: projects whose kind lacks it can't run it.
+    pub fn layer(&self) -> Layer {
+        match self {
+            GenKind::Service | GenKind::Test | GenKind::AllTests => Layer::Service,
+            GenKind::Usecase => Layer::Usecase,
+            GenKind::Repository => Layer::Repository,
+            GenKind::Data => Layer::Data,
+            GenKind::Handler | GenKind::Grpc | GenKind::Graphql | GenKind::Example | GenKind::IntegrationTest => Layer::Handler,
+            GenKind::Adapter(_) | GenKind::AdapterAll | GenKind::Migration(_) | GenKind::MigrationAll => Layer::Adapter,
+            GenKind::Config => Layer::Config,
+            GenKind::Factory => Layer::Factory,
+        }
+    }
+}
+
+pub fn dispatch(kind: GenKind, ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let project = ctx.config.kind;
+    if !project.has(kind.layer()) {
+        return Err(RvyError::Usage(format!(
+            "{} projects have no {} layer (kind in rvy.toml)",
+            project.name(),
+            kind.layer().key()
+        )));
+    }
+    ctx.report.within(kind.name(), || generate(kind, ctx, name))?;
+    // What `rvy regen` renders the entity with when no fields are given
+    if !ctx.fields.is_empty() {
+        manifest::record_entity(&*ctx.fs, name, &ctx.fields)?;
+    }
+    Ok(())
+}
+
+fn generate(kind: GenKind, ctx: &Context, name: &str) -> Result<(), RvyError> {
     match kind {
         GenKind::Service => service::generate(ctx, name),
         GenKind::Usecase => usecase::generate(ctx, name),
         GenKind::Repository => repository::generate(ctx, name),
         GenKind::Data => data::generate(ctx, name),
         GenKind::Handler => handler::generate(ctx, name),
+        GenKind::Grpc => grpc::generate(ctx, name),
+        GenKind::Graphql => graphql::generate(ctx, name),
         GenKind::Adapter(db_type) => adapter::generate(ctx, name, &db_type),
         GenKind::AdapterAll => adapter::generate_all(ctx, name),
         GenKind::Config => adapter::generate_config(ctx, name),
@@ -34,34 +95,65 @@ pub fn dispatch(kind: GenKind, ctx: &Context, name: &str) {
         GenKind::Test => test::generate_unit_tests(ctx, name),
         GenKind::IntegrationTest => test::generate_integration_tests(ctx, name),
         GenKind::AllTests => test::generate_all_tests(ctx, name),
-        GenKind::Migration(db_type) => {
-            if let Err(e) = migration::generate_migration(ctx, name, &db_type) {
-                eprintln!("❌ Error generating migration: {}", e);
-                std::process::exit(1);
-            }
-        }
-        GenKind::MigrationAll => {
-            if let Err(e) = migration::generate_migration(ctx, name, "all") {
-                eprintln!("❌ Error generating migrations: {}", e);
-                std::process::exit(1);
-            }
-        }
+        GenKind::Migration(db_type) => migration::generate_migration(ctx, name, &db_type),
+        GenKind::MigrationAll => migration::generate_migration(ctx, name, "all"),
     }
 }
 
-pub fn generate_all(ctx: &Context, name: &str) {
+pub fn generate_all(ctx: &Context, name: &str) -> Result<(), RvyError> {
     // Generate error module first (only once, not per entity)
-    error::generate_error_module(ctx, name);
+    step("error module", || ctx.report.within("error", || error::generate_error_module(ctx, name)))?;
     
-    dispatch(GenKind::Service, ctx, name);
-    dispatch(GenKind::Usecase, ctx, name);
-    dispatch(GenKind::Repository, ctx, name);
-    dispatch(GenKind::Data, ctx, name);
-    dispatch(GenKind::Handler, ctx, name);
-    dispatch(GenKind::AdapterAll, ctx, name);
-    dispatch(GenKind::Config, ctx, name);
-    dispatch(GenKind::Factory, ctx, name);
-    dispatch(GenKind::Example, ctx, name);
-    dispatch(GenKind::AllTests, ctx, name); // Add tests
-    dispatch(GenKind::MigrationAll, ctx, name); // Add migrations for all databases
+    generate_step(ctx, "service", GenKind::Service, name)?;
+    generate_step(ctx, "usecase", GenKind::Usecase, name)?;
+    generate_step(ctx, "repository", GenKind::Repository, name)?;
+    generate_step(ctx, "data", GenKind::Data, name)?;
+    generate_step(ctx, "handler", GenKind::Handler, name)?;
+    if grpc::generated(ctx, name) {
+        generate_step(ctx, "grpc", GenKind::Grpc, name)?;
+    }
+    if graphql::generated(ctx, name) {
+        generate_step(ctx, "graphql", GenKind::Graphql, name)?;
+    }
+    generate_step(ctx, "adapters", GenKind::AdapterAll, name)?;
+    generate_step(ctx, "config", GenKind::Config, name)?;
+    generate_step(ctx, "factory", GenKind::Factory, name)?;
+    generate_step(ctx, "example", GenKind::Example, name)?;
+    generate_step(ctx, "tests", GenKind::AllTests, name)?; // Add tests
+    if !ctx.config.kind.has(Layer::Adapter) {
+        // No database code, so nothing to migrate
+    } else if ctx.merge {
+        // A changed model needs a new ALTER migration, not a rewritten CREATE
+        ctx.report.within("migration", || ctx.report.tip("Migrations are not regenerated; add a migration for any schema change"));
+    } else {
+        step("migrations", || dispatch(GenKind::MigrationAll, ctx, name))?; // Add migrations for all databases
+    }
+    // The crate root needs every layer the generated code refers to
+    step("module declarations", || declare_modules(ctx))
+}
+
+// Layers the project kind lacks are left out of gen-all
+fn generate_step(ctx: &Context, step_name: &str, kind: GenKind, name: &str) -> Result<(), RvyError> {
+    if !ctx.config.kind.has(kind.layer()) {
+        return Ok(());
+    }
+    step(step_name, || dispatch(kind, ctx, name))
+}
+
+pub fn generate_entities(ctx: &Context, entities: &[Entity]) -> Result<(), RvyError> {
+    for entity in entities {
+        ctx.report.info(&format!("📦 Generating {}", entity.name));
+
+        let entity_ctx = Context {
+            is_new_all: true,
+            fields: entity.fields.clone(),
+            databases: entity.databases.clone(),
+            plural: entity.plural.clone(),
+            table: entity.table.clone(),
+            permissions: entity.permissions.clone(),
+            ..ctx.clone()
+        };
+        step(&entity.name, || generate_all(&entity_ctx, &entity.name))?;
+    }
+    Ok(())
 }
@@ -1,54 +1,36 @@
 use crate::context::Context;
-use std::fs;
-use std::io::Write;
+use crate::error::RvyError;
+use crate::generator::cargo::{self, dep};
+use crate::generator::{declare_modules, manifest, render};
 use std::path::Path;
 
-pub fn generate_error_module(_ctx: &Context, _name: &str) {
-    let template = include_str!("../../templates/error.rs.tpl");
-    
-    // Create src directory if it doesn't exist
-    let src_dir = Path::new("src");
-    if !src_dir.exists() {
-        fs::create_dir_all(src_dir).expect("Failed to create src directory");
-    }
-    
-    let file_path = src_dir.join("error.rs");
-    
-    // Check if file already exists
-    if file_path.exists() {
-        println!("⏭️  Error module already exists: src/error.rs");
-        return;
-    }
-    
-    // Write the error module
-    let mut file = fs::File::create(&file_path).expect("Failed to create error.rs");
-    file.write_all(template.as_bytes())
-        .expect("Failed to write error.rs");
-    
-    println!("✅ Generated error module: src/error.rs");
-    
-    // Update lib.rs to include error module
-    update_lib_rs();
-}
+pub fn generate_error_module(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let template = "error.rs.tpl";
+    let content = render(ctx, template, name)?;
 
-fn update_lib_rs() {
-    let lib_path = Path::new("src/lib.rs");
+    let file_path = Path::new("src/error.rs");
     
-    if !lib_path.exists() {
-        println!("⚠️  Warning: src/lib.rs not found, skipping update");
-        return;
+    // Check if file already exists
+    if ctx.fs.exists(file_path) {
+        ctx.report.skipped("src/error.rs", "already exists");
+        return Ok(());
     }
     
-    let content = fs::read_to_string(lib_path).expect("Failed to read lib.rs");
-    
-    // Check if error module is already declared
-    if content.contains("pub mod error;") {
-        return;
+    let mut deps = vec![dep("serde_json", "1.0")];
+    if ctx.config.kind.http() {
+        // Errors turn into JSON responses
+        deps.push(cargo::web_crate(ctx.config.web));
+        deps.push(dep("serde", "1.0").features(&["derive"]));
     }
+    deps.extend(cargo::database_crates(ctx.config.code_databases()));
+    cargo::require(ctx, &deps)?;
+
+    // Write the error module
+    ctx.fs.write(file_path, &content).map_err(|e| RvyError::io("src/error.rs", e))?;
+    manifest::record(&*ctx.fs, "src/error.rs", &content, Some(template))?;
     
-    // Add error module declaration at the beginning
-    let new_content = format!("pub mod error;\n{}", content);
+    ctx.report.created("src/error.rs");
     
-    fs::write(lib_path, new_content).expect("Failed to update lib.rs");
-    println!("✅ Updated src/lib.rs with error module");
+    // Declare the error module in main.rs and lib.rs
+    declare_modules(ctx)
 }
@@ -0,0 +1,398 @@
+// Typed entity fields passed with `--field name:type[?]`.
+//
+// `id`, `created_at` and `updated_at` are always generated; the fields
+// declared here are the entity's own columns. Templates loop over them as
+// `fields`, see `Field::to_value` for the attributes available.
+
+use minijinja::context;
+use minijinja::value::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Text,
+    Int,
+    BigInt,
+    Float,
+    Decimal,
+    Bool,
+    Uuid,
+    Date,
+    DateTime,
+    Json,
+}
+
+pub const SUPPORTED_TYPES: &str =
+    "string, text, int, bigint, float, decimal, bool, uuid, date, datetime, json";
+
+impl FieldType {
+    pub fn parse(s: &str) -> Option<Self> {
+        let ty = match s.to_lowercase().as_str() {
+            "string" | "str" => FieldType::String,
+            "text" => FieldType::Text,
+            "int" | "i32" | "integer" => FieldType::Int,
+            "bigint" | "i64" | "long" => FieldType::BigInt,
+            "float" | "f64" | "double" => FieldType::Float,
+            "decimal" | "numeric" => FieldType::Decimal,
+            "bool" | "boolean" => FieldType::Bool,
+            "uuid" => FieldType::Uuid,
+            "date" => FieldType::Date,
+            "datetime" | "timestamp" => FieldType::DateTime,
+            "json" => FieldType::Json,
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            FieldType::String => "string",
+            FieldType::Text => "text",
+            FieldType::Int => "int",
+            FieldType::BigInt => "bigint",
+            FieldType::Float => "float",
+            FieldType::Decimal => "decimal",
+            FieldType::Bool => "bool",
+            FieldType::Uuid => "uuid",
+            FieldType::Date => "date",
+            FieldType::DateTime => "datetime",
+            FieldType::Json => "json",
+        }
+    }
+
+    pub fn rust_type(&self) -> &'static str {
+        match self {
+            FieldType::String | FieldType::Text => "String",
+            FieldType::Int => "i32",
+            FieldType::BigInt => "i64",
+            FieldType::Float => "f64",
+            FieldType::Decimal => "rust_decimal::Decimal",
+            FieldType::Bool => "bool",
+            FieldType::Uuid => "uuid::Uuid",
+            FieldType::Date => "chrono::NaiveDate",
+            FieldType::DateTime => "chrono::DateTime<chrono::Utc>",
+            FieldType::Json => "serde_json::Value",
+        }
+    }
+
+    pub fn postgres_type(&self) -> &'static str {
+        match self {
+            FieldType::String => "VARCHAR(255)",
+            FieldType::Text => "TEXT",
+            FieldType::Int => "INTEGER",
+            FieldType::BigInt => "BIGINT",
+            FieldType::Float => "DOUBLE PRECISION",
+            FieldType::Decimal => "NUMERIC(18, 4)",
+            FieldType::Bool => "BOOLEAN",
+            FieldType::Uuid => "UUID",
+            FieldType::Date => "DATE",
+            FieldType::DateTime => "TIMESTAMPTZ",
+            FieldType::Json => "JSONB",
+        }
+    }
+
+    pub fn mysql_type(&self) -> &'static str {
+        match self {
+            FieldType::String => "VARCHAR(255)",
+            FieldType::Text => "TEXT",
+            FieldType::Int => "INT",
+            FieldType::BigInt => "BIGINT",
+            FieldType::Float => "DOUBLE",
+            FieldType::Decimal => "DECIMAL(18, 4)",
+            FieldType::Bool => "BOOLEAN",
+            FieldType::Uuid => "BINARY(16)",
+            FieldType::Date => "DATE",
+            FieldType::DateTime => "TIMESTAMP",
+            FieldType::Json => "JSON",
+        }
+    }
+
+    pub fn sqlite_type(&self) -> &'static str {
+        match self {
+            FieldType::String | FieldType::Text => "TEXT",
+            FieldType::Int | FieldType::BigInt => "INTEGER",
+            FieldType::Float => "REAL",
+            // sqlx has no SQLite codec for Decimal, it goes through `sqlx::types::Text`
+            FieldType::Decimal => "TEXT",
+            FieldType::Bool => "BOOLEAN",
+            FieldType::Uuid => "BLOB",
+            FieldType::Date => "DATE",
+            FieldType::DateTime => "DATETIME",
+            FieldType::Json => "TEXT",
+        }
+    }
+
+    pub fn bson_type(&self) -> &'static str {
+        match self {
+            FieldType::Int => "int",
+            FieldType::BigInt => "long",
+            FieldType::Float => "double",
+            FieldType::Bool => "bool",
+            FieldType::Json => "object",
+            // Decimal, Uuid and chrono types are serialized as strings by serde
+            _ => "string",
+        }
+    }
+
+    // Types without a protobuf scalar travel as strings
+    pub fn proto_type(&self) -> &'static str {
+        match self {
+            FieldType::Int => "int32",
+            FieldType::BigInt => "int64",
+            FieldType::Float => "double",
+            FieldType::Bool => "bool",
+            _ => "string",
+        }
+    }
+
+    fn is_proto_scalar(&self) -> bool {
+        matches!(self, FieldType::String | FieldType::Text | FieldType::Int | FieldType::BigInt | FieldType::Float | FieldType::Bool)
+    }
+
+    fn is_string(&self) -> bool {
+        matches!(self, FieldType::String | FieldType::Text)
+    }
+
+    fn sample_rust(&self, label: &str) -> String {
+        match self {
+            FieldType::String | FieldType::Text => format!("\"{}\".to_string()", label),
+            FieldType::Int | FieldType::BigInt => "1".to_string(),
+            FieldType::Float => "1.0".to_string(),
+            FieldType::Decimal => "rust_decimal::Decimal::new(100, 2)".to_string(),
+            FieldType::Bool => "true".to_string(),
+            FieldType::Uuid => "uuid::Uuid::nil()".to_string(),
+            FieldType::Date => "chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()".to_string(),
+            FieldType::DateTime => "chrono::Utc::now()".to_string(),
+            FieldType::Json => "serde_json::json!({})".to_string(),
+        }
+    }
+
+    fn sample_json(&self, label: &str) -> String {
+        match self {
+            FieldType::String | FieldType::Text => format!("\"{}\"", label),
+            FieldType::Int | FieldType::BigInt => "1".to_string(),
+            FieldType::Float => "1.0".to_string(),
+            FieldType::Decimal => "\"1.00\"".to_string(),
+            FieldType::Bool => "true".to_string(),
+            FieldType::Uuid => "\"00000000-0000-0000-0000-000000000000\"".to_string(),
+            FieldType::Date => "\"2024-01-01\"".to_string(),
+            FieldType::DateTime => "\"2024-01-01T00:00:00Z\"".to_string(),
+            FieldType::Json => "{}".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub ty: FieldType,
+    pub nullable: bool,
+    pub unique: bool,
+    pub default: Option<String>,  // Raw SQL default expression
+}
+
+const RESERVED: &[&str] = &["id", "created_at", "updated_at"];
+
+// Strict, reserved and 2024-edition keywords, none of which can name a field
+// or module
+pub const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+impl Field {
+    /// Parse a `name:type` or `name:type?` definition (a trailing `?` makes it nullable).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (name, ty) = s
+            .split_once(':')
+            .ok_or_else(|| format!("invalid field '{}', expected NAME:TYPE", s))?;
+
+        let (ty, nullable) = match ty.trim().strip_suffix('?') {
+            Some(ty) => (ty, true),
+            None => (ty.trim(), false),
+        };
+
+        Self::new(name.trim(), ty, nullable)
+    }
+
+    pub fn new(name: &str, ty: &str, nullable: bool) -> Result<Self, String> {
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+            && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        // `_` alone is a pattern, not an identifier
+        if !valid_name || name == "_" {
+            return Err(format!("invalid field name '{}', use snake_case", name));
+        }
+        if RUST_KEYWORDS.contains(&name) {
+            return Err(format!("field name '{}' is a Rust keyword", name));
+        }
+        if RESERVED.contains(&name) {
+            return Err(format!("field '{}' is always generated and cannot be redefined", name));
+        }
+
+        let ty = FieldType::parse(ty).ok_or_else(|| {
+            format!("unknown field type '{}' (supported: {})", ty, SUPPORTED_TYPES)
+        })?;
+
+        Ok(Self {
+            name: name.to_string(),
+            ty,
+            nullable,
+            unique: false,
+            default: None,
+        })
+    }
+
+    pub fn rust_type(&self) -> String {
+        if self.nullable {
+            format!("Option<{}>", self.ty.rust_type())
+        } else {
+            self.ty.rust_type().to_string()
+        }
+    }
+
+    fn sample_rust(&self) -> String {
+        let value = self.ty.sample_rust(&format!("Test {}", self.name));
+        if self.nullable {
+            format!("Some({})", value)
+        } else {
+            value
+        }
+    }
+
+    fn sample_json(&self) -> String {
+        if self.nullable {
+            "null".to_string()
+        } else {
+            self.ty.sample_json(&format!("Test {}", self.name))
+        }
+    }
+
+    // Expression turning `item.<field>` into its protobuf value
+    fn encode_proto(&self) -> String {
+        let field = format!("item.{}", self.name);
+        if self.ty.is_proto_scalar() {
+            return field;
+        }
+        let encode = |value: &str| match self.ty {
+            FieldType::DateTime => format!("{}.to_rfc3339()", value),
+            _ => format!("{}.to_string()", value),
+        };
+        if self.nullable {
+            format!("{}.map(|value| {})", field, encode("value"))
+        } else {
+            encode(&field)
+        }
+    }
+
+    // Expression reading the field back from `message.<field>`, returning
+    // early with `invalid_field` when a string doesn't parse
+    fn decode_proto(&self) -> String {
+        let field = format!("message.{}", self.name);
+        if self.ty.is_proto_scalar() {
+            return field;
+        }
+        let error = format!("map_err(|_| invalid_field(\"{}\"))?", self.name);
+        if self.nullable {
+            format!("{}.map(|value| value.parse()).transpose().{}", field, error)
+        } else {
+            format!("{}.parse().{}", field, error)
+        }
+    }
+
+    /// The field as seen by templates.
+    pub fn to_value(&self) -> Value {
+        context! {
+            name => self.name,
+            type => self.ty.key(),
+            rust_type => self.rust_type(),
+            postgres_type => self.ty.postgres_type(),
+            mysql_type => self.ty.mysql_type(),
+            sqlite_type => self.ty.sqlite_type(),
+            bson_type => self.ty.bson_type(),
+            proto_type => self.ty.proto_type(),
+            to_proto => self.encode_proto(),
+            from_proto => self.decode_proto(),
+            nullable => self.nullable,
+            unique => self.unique,
+            default => self.default,
+            sample => self.sample_rust(),
+            json_sample => self.sample_json(),
+        }
+    }
+}
+
+/// Fields used when none are given, matching the classic `name`-only entity.
+pub fn default_fields() -> Vec<Field> {
+    vec![Field {
+        name: "name".to_string(),
+        ty: FieldType::String,
+        nullable: false,
+        unique: false,
+        default: None,
+    }]
+}
+
+/// Apply defaults and validate a field list as a whole.
+pub fn resolve(fields: Vec<Field>) -> Result<Vec<Field>, String> {
+    if fields.is_empty() {
+        return Ok(default_fields());
+    }
+
+    for (i, field) in fields.iter().enumerate() {
+        if fields[..i].iter().any(|f| f.name == field.name) {
+            return Err(format!("field '{}' is defined more than once", field.name));
+        }
+    }
+
+    if label_field(&fields).is_none() {
+        return Err(
+            "at least one non-nullable string/text field is required (used by find_by_name and search)"
+                .to_string(),
+        );
+    }
+
+    Ok(fields)
+}
+
+/// The field backing `find_by_name`, `search` and the usecase validation:
+/// `name` when present, otherwise the first non-nullable string field.
+pub fn label_field(fields: &[Field]) -> Option<&Field> {
+    let candidates = || fields.iter().filter(|f| f.ty.is_string() && !f.nullable);
+    candidates()
+        .find(|f| f.name == "name")
+        .or_else(|| candidates().next())
+}
+
+pub fn needs_crate(fields: &[Field], crate_name: &str) -> bool {
+    fields.iter().any(|f| match crate_name {
+        "rust_decimal" => f.ty == FieldType::Decimal,
+        "uuid" => f.ty == FieldType::Uuid,
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_keywords_as_field_names() {
+        for keyword in ["type", "box", "try", "yield", "abstract", "final", "macro", "override", "priv", "typeof", "unsized", "virtual", "become", "do", "gen"] {
+            assert!(Field::new(keyword, "string", false).is_err(), "{} was accepted", keyword);
+        }
+        assert!(Field::new("boxed", "string", false).is_ok());
+    }
+
+    #[test]
+    fn rejects_underscore_as_field_name() {
+        assert!(Field::new("_", "string", false).is_err());
+        assert!(Field::parse("_:string").is_err());
+        assert!(Field::new("_hidden", "string", false).is_ok());
+    }
+}
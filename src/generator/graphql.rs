@@ -0,0 +1,256 @@
+// `rvy gen graphql <entity>`: async-graphql queries and mutations on the
+// entity's service. One schema, `handler/graphql.rs`, merges the resolvers of
+// every entity that has them and is served at /graphql next to Swagger UI.
+
+use std::path::Path;
+
+use minijinja::context;
+
+use crate::config::{Layer, WebFramework};
+use crate::context::Context;
+use crate::error::RvyError;
+use crate::generator::cargo::{self, crate_name, dep};
+use crate::generator::field::needs_crate;
+use crate::generator::manifest;
+use crate::generator::rust_file::{calls_path, uses_var, RustFile};
+use crate::generator::{
+    overwrite_allowed, remove_module_export, render, template, test, update_module_exports, write_generated, write_rendered,
+};
+
+const SCHEMA_MODULE: &str = "graphql";
+const SCHEMA_TEMPLATE: &str = "graphql/schema.rs.tpl";
+
+pub fn generate(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let config = &ctx.config;
+    // The resolvers call the entity's service and return its AppError
+    for path in ["src/error.rs".to_string(), config.file_path(Layer::Service, name)] {
+        if !ctx.fs.exists(Path::new(&path)) {
+            return Err(RvyError::Usage(format!("{} does not exist. Generate the entity first.", path)));
+        }
+    }
+    let mut features = vec!["chrono"];
+    for (krate, feature) in [("rust_decimal", "decimal"), ("uuid", "uuid")] {
+        if needs_crate(&ctx.fields, krate) {
+            features.push(feature);
+        }
+    }
+    cargo::require(ctx, &[
+        dep("async-graphql", "7").features(&features),
+        dep("tokio", "1").features(&["full"]),
+        dep("serde_json", "1.0"),
+        dep("async-trait", "0.1"),
+    ])?;
+
+    write_rendered(ctx, &entity_path(ctx, name), "graphql/entity.rs.tpl", name)?;
+    update_module_exports(ctx, &config.mod_file(Layer::Handler), &graphql_module(name))?;
+
+    let mut entities = entities(ctx, None)?;
+    if !entities.iter().any(|entity| entity == name) {
+        entities.push(name.to_string());
+    }
+    write_schema(ctx, &entities)?;
+    update_module_exports(ctx, &config.mod_file(Layer::Handler), SCHEMA_MODULE)?;
+
+    if !service_has_search(ctx, name)? {
+        ctx.report.warning(
+            Some(&config.file_path(Layer::Service, name)),
+            &format!("no search or count, run `rvy regen {}` for the resolvers to compile", name),
+        );
+    }
+
+    register_schema(ctx, name)?;
+    generate_test(ctx, name)
+}
+
+/// Whether the entity has GraphQL resolvers, which `gen-all`, `sync` and
+/// `regen` then keep in step with its fields.
+pub fn generated(ctx: &Context, name: &str) -> bool {
+    ctx.fs.exists(Path::new(&entity_path(ctx, name)))
+}
+
+fn entity_path(ctx: &Context, name: &str) -> String {
+    format!("{}/{}.rs", ctx.config.layer(Layer::Handler).dir, graphql_module(name))
+}
+
+fn graphql_module(name: &str) -> String {
+    format!("{}_graphql", name)
+}
+
+fn schema_path(ctx: &Context) -> String {
+    format!("{}/{}.rs", ctx.config.layer(Layer::Handler).dir, SCHEMA_MODULE)
+}
+
+fn test_path(ctx: &Context, name: &str) -> String {
+    format!("{}/{}_graphql_test.rs", ctx.config.tests_dir, name)
+}
+
+// Entities with resolvers in the handler directory, but `except`
+fn entities(ctx: &Context, except: Option<&str>) -> Result<Vec<String>, RvyError> {
+    let dir = &ctx.config.layer(Layer::Handler).dir;
+    let Ok(files) = ctx.fs.list(Path::new(dir)) else {
+        return Ok(Vec::new());
+    };
+    let mut entities: Vec<String> = files
+        .iter()
+        .filter_map(|file| file.strip_suffix("_graphql.rs"))
+        .filter(|entity| Some(*entity) != except)
+        .map(str::to_string)
+        .collect();
+    entities.sort();
+    Ok(entities)
+}
+
+// The schema is rvy's to rewrite whenever the entities change, unless it was edited
+fn write_schema(ctx: &Context, names: &[String]) -> Result<(), RvyError> {
+    let path = schema_path(ctx);
+    let entities: Vec<_> = names
+        .iter()
+        .map(|name| context! { module => graphql_module(name), Name => ctx.naming(name).pascal })
+        .collect();
+    let vars = context! { web => ctx.config.web.name(), entities };
+    let content = template::render(SCHEMA_TEMPLATE, vars).map_err(|e| RvyError::Template(format!("{:#}", e)))?;
+
+    let exists = ctx.fs.exists(Path::new(&path));
+    if exists {
+        let current = ctx.fs.read(Path::new(&path)).map_err(|e| RvyError::io(&path, e))?;
+        if current == content || !overwrite_allowed(ctx, &path)? {
+            return Ok(());
+        }
+    }
+    ctx.fs.write(Path::new(&path), &content).map_err(|e| RvyError::io(&path, e))?;
+    manifest::record(&*ctx.fs, &path, &content, Some(SCHEMA_TEMPLATE))?;
+    if exists {
+        ctx.report.modified(&path, None);
+    } else {
+        ctx.report.created(&path);
+    }
+    Ok(())
+}
+
+// Services generated before `search` and `count` were added lack them
+fn service_has_search(ctx: &Context, name: &str) -> Result<bool, RvyError> {
+    let path = ctx.config.file_path(Layer::Service, name);
+    let service = ctx.fs.read(Path::new(&path)).map_err(|e| RvyError::io(&path, e))?;
+    Ok(service.contains("fn search") && service.contains("fn count"))
+}
+
+// Hand the entity's service to the schema (`let graphql = schema().data(..)`)
+// and mount the schema on the app the first time
+fn register_schema(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let main_path = "src/main.rs";
+    if !ctx.fs.exists(Path::new(main_path)) {
+        return Ok(());
+    }
+
+    let schema_mod = format!("{}::{}", ctx.config.module_path(Layer::Handler), SCHEMA_MODULE);
+    let service = format!("service_{}", ctx.naming(name).snake);
+    let data = format!("{}.clone()", service);
+
+    let mut main = RustFile::read(&*ctx.fs, main_path)?;
+    let edit = |e| RvyError::edit(main_path, e);
+    if !main.has_fn("main") || !main.has_local("main", &service).map_err(edit)? {
+        ctx.report.tip(&format!(
+            "Serve it with {}::schema().data(service).finish() and {}::routes(schema, auth) in main.rs",
+            schema_mod, schema_mod
+        ));
+        return Ok(());
+    }
+
+    if main.has_local("main", "graphql").map_err(edit)? {
+        let registered = main
+            .has_chain_call("main", "graphql", |call| call.method == "data" && call.args.first().is_some_and(|arg| uses_var(arg, &service)))
+            .map_err(edit)?;
+        if registered {
+            return Ok(());
+        }
+        main.add_chain_call("main", "graphql", "data", &data, |_| true).map_err(edit)?;
+        return main.save(ctx);
+    }
+
+    let mut stmts = vec![format!("let graphql = {}::schema()\n        .data({})\n        .finish();", schema_mod, data)];
+    if !main.has_local("main", "auth").map_err(edit)? {
+        stmts.insert(0, "let auth = std::sync::Arc::new(auth::AuthConfig::from_env()?);".to_string());
+    }
+    main.insert_stmts("main", "service", "app", &stmts).map_err(edit)?;
+    if main.has_local("main", "app").map_err(edit)? {
+        // actix builds the app once per worker, so each gets its own handle on the schema
+        let (mount, routes) = match ctx.config.web {
+            WebFramework::Axum => ("merge", format!("{}::routes(graphql, auth.clone())", schema_mod)),
+            WebFramework::Actix => ("configure", format!("{}::routes(graphql.clone(), auth.clone())", schema_mod)),
+        };
+        main.add_chain_call("main", "app", mount, &routes, |arg| calls_path(arg, &["routes"])).map_err(edit)?;
+        ctx.report.info(&format!("GraphQL playground at http://127.0.0.1:{}/graphql", ctx.config.port));
+    } else {
+        ctx.report.tip(&format!("Mount {}::routes(graphql, auth) on your app in main.rs", schema_mod));
+    }
+    main.save(ctx)
+}
+
+fn generate_test(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    test::create_test_common(ctx, name)?;
+
+    let template = "graphql/test.rs.tpl";
+    let crate_name = crate_name(ctx).unwrap_or_else(|| name.to_string());
+    let content = render(ctx, template, name)?.replace("crate::", &format!("{}::", crate_name));
+    write_generated(ctx, &test_path(ctx, name), &content, Some(template))
+}
+
+/// Files `generate` writes for the entity, for `rvy destroy`.
+pub fn files(ctx: &Context, name: &str) -> Vec<String> {
+    vec![
+        entity_path(ctx, name),
+        test_path(ctx, name),
+    ]
+}
+
+/// The entity's `mod` line in the handler mod.rs, for `rvy destroy`.
+pub fn module(ctx: &Context, name: &str) -> (String, String) {
+    (ctx.config.mod_file(Layer::Handler), graphql_module(name))
+}
+
+/// Undo `register_schema` and drop the entity from the schema. The schema,
+/// its mount and `let graphql` go with the last entity in it.
+pub fn remove_schema(ctx: &Context, name: &str) -> Result<bool, RvyError> {
+    if !generated(ctx, name) {
+        return Ok(false);
+    }
+
+    let remaining = entities(ctx, Some(name))?;
+    let main_path = "src/main.rs";
+    if ctx.fs.exists(Path::new(main_path)) {
+        let service = format!("service_{}", ctx.naming(name).snake);
+        let mut main = RustFile::read(&*ctx.fs, main_path)?;
+        let edit = |e| RvyError::edit(main_path, e);
+        let mut changed = false;
+        if main.has_fn("main") && main.has_local("main", "graphql").map_err(edit)? {
+            changed |= main
+                .remove_chain_call("main", "graphql", |call| call.method == "data" && call.args.first().is_some_and(|arg| uses_var(arg, &service)))
+                .map_err(edit)?;
+            if remaining.is_empty() {
+                changed |= main.remove_local("main", "graphql").map_err(edit)?;
+                if main.has_local("main", "app").map_err(edit)? {
+                    changed |= main
+                        .remove_chain_call("main", "app", |call| {
+                            call.args.first().is_some_and(|arg| calls_path(arg, &[SCHEMA_MODULE, "routes"]))
+                        })
+                        .map_err(edit)?;
+                }
+            }
+        }
+        if changed {
+            main.save(ctx)?;
+        }
+    }
+
+    if !remaining.is_empty() {
+        return write_schema(ctx, &remaining).map(|_| true);
+    }
+    let path = schema_path(ctx);
+    if ctx.fs.exists(Path::new(&path)) && overwrite_allowed(ctx, &path)? {
+        ctx.fs.remove(Path::new(&path)).map_err(|e| RvyError::io(&path, e))?;
+        manifest::forget(&*ctx.fs, &path)?;
+        ctx.report.deleted(&path);
+    }
+    remove_module_export(ctx, &ctx.config.mod_file(Layer::Handler), SCHEMA_MODULE)?;
+    Ok(true)
+}
@@ -0,0 +1,202 @@
+// `rvy gen grpc <entity>`: a tonic server in front of the entity's service,
+// next to its REST handler. The messages come from `proto/<entity>.proto`,
+// compiled by a build.rs shared by all entities.
+
+use std::path::Path;
+
+use minijinja::context;
+
+use crate::config::Layer;
+use crate::context::Context;
+use crate::error::RvyError;
+use crate::generator::cargo::{self, crate_name, dep};
+use crate::generator::rust_file::{calls_path, RustFile};
+use crate::generator::{render, template, test, update_module_exports, write_generated, write_rendered};
+
+const BUILD_SCRIPT: &str = "build.rs";
+
+pub fn generate(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let config = &ctx.config;
+    cargo::require(ctx, &[
+        dep("tonic", "0.12"),
+        dep("prost", "0.13"),
+        dep("tokio", "1").features(&["full"]),
+        dep("tonic-build", "0.12").build(),
+        dep("protoc-bin-vendored", "3").build(),
+        // The integration test talks to the server over an in-memory pipe
+        dep("tokio-stream", "0.1").dev(),
+        dep("tower", "0.5").features(&["util"]).dev(),
+        dep("hyper-util", "0.1").features(&["tokio"]).dev(),
+        dep("async-trait", "0.1"),
+    ])?;
+
+    write_rendered(ctx, &proto_path(name), "grpc/entity.proto.tpl", name)?;
+    generate_build_script(ctx, name)?;
+
+    write_rendered(ctx, &server_path(ctx, name), "grpc/server.rs.tpl", name)?;
+    update_module_exports(ctx, &config.mod_file(Layer::Handler), &grpc_module(name))?;
+
+    add_status_conversion(ctx)?;
+    register_server(ctx, name)?;
+    generate_test(ctx, name)
+}
+
+/// Whether the entity has a gRPC server, which `gen-all`, `sync` and
+/// `regen` then keep in step with its fields.
+pub fn generated(ctx: &Context, name: &str) -> bool {
+    ctx.fs.exists(Path::new(&server_path(ctx, name)))
+}
+
+fn server_path(ctx: &Context, name: &str) -> String {
+    format!("{}/{}.rs", ctx.config.layer(Layer::Handler).dir, grpc_module(name))
+}
+
+fn proto_path(name: &str) -> String {
+    format!("proto/{}.proto", name)
+}
+
+fn grpc_module(name: &str) -> String {
+    format!("{}_grpc", name)
+}
+
+fn test_path(ctx: &Context, name: &str) -> String {
+    format!("{}/{}_grpc_test.rs", ctx.config.tests_dir, name)
+}
+
+// The build script compiles every file in proto/, so it is only written once
+fn generate_build_script(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    if !ctx.fs.exists(Path::new(BUILD_SCRIPT)) {
+        return write_rendered(ctx, BUILD_SCRIPT, "grpc/build.rs.tpl", name);
+    }
+    let script = ctx.fs.read(Path::new(BUILD_SCRIPT)).map_err(|e| RvyError::io(BUILD_SCRIPT, e))?;
+    if !script.contains("tonic_build") {
+        ctx.report.warning(Some(BUILD_SCRIPT), &format!("compile {} with tonic_build", proto_path(name)));
+    }
+    Ok(())
+}
+
+// `?` on the service's results needs `From<AppError> for tonic::Status`
+fn add_status_conversion(ctx: &Context) -> Result<(), RvyError> {
+    let error_path = "src/error.rs";
+    if !ctx.fs.exists(Path::new(error_path)) {
+        return Err(RvyError::Usage(format!("{} does not exist. Generate the entity first.", error_path)));
+    }
+    let mut error = RustFile::read(&*ctx.fs, error_path)?;
+    if error.source().contains("for tonic::Status") {
+        return Ok(());
+    }
+    // error.rs files generated before AppError::Forbidden lack the variant
+    let vars = context! { has_forbidden => error.source().contains("Forbidden(") };
+    let conversion = template::render("grpc/status.rs.tpl", vars).map_err(|e| RvyError::Template(format!("{:#}", e)))?;
+    error.append_item(&conversion).map_err(|e| RvyError::edit(error_path, e))?;
+    error.save(ctx)
+}
+
+// Serve the entity next to the HTTP server, on one tonic server shared by
+// all entities (`let grpc = Server::builder().add_service(..)..`)
+fn register_server(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let main_path = "src/main.rs";
+    if !ctx.fs.exists(Path::new(main_path)) {
+        return Ok(());
+    }
+
+    let naming = ctx.naming(name);
+    let grpc_mod = format!("{}::{}", ctx.config.module_path(Layer::Handler), grpc_module(&naming.snake));
+    let grpc_ty = format!("{}Grpc", naming.pascal);
+    let service = format!("service_{}", naming.snake);
+
+    let mut main = RustFile::read(&*ctx.fs, main_path)?;
+    let edit = |e| RvyError::edit(main_path, e);
+    if main.has_use(&grpc_mod) {
+        return Ok(());
+    }
+    if !main.has_fn("main") || !main.has_local("main", &service).map_err(edit)? {
+        ctx.report.tip(&format!(
+            "Serve it with tonic::transport::Server::builder().add_service({}::server(service, auth)) in main.rs",
+            grpc_ty
+        ));
+        return Ok(());
+    }
+
+    main.add_use(&format!("use {}::{};", grpc_mod, grpc_ty)).map_err(edit)?;
+    let server = format!("{}::server({}.clone(), auth.clone())", grpc_ty, service);
+    if main.has_local("main", "grpc").map_err(edit)? {
+        main.add_chain_call("main", "grpc", "add_service", &server, |arg| calls_path(arg, &["server"]))
+            .map_err(edit)?;
+    } else {
+        let mut stmts = Vec::new();
+        if !main.has_local("main", "auth").map_err(edit)? {
+            stmts.push("let auth = std::sync::Arc::new(auth::AuthConfig::from_env()?);".to_string());
+        }
+        stmts.extend([
+            format!("let grpc_addr = \"127.0.0.1:{}\".parse()?;", ctx.config.grpc_port),
+            format!("let grpc = tonic::transport::Server::builder()\n        .add_service({});", server),
+            [
+                "tokio::spawn(async move {",
+                "        println!(\"✅ gRPC listening on {}\", grpc_addr);",
+                "        if let Err(e) = grpc.serve(grpc_addr).await {",
+                "            eprintln!(\"❌ gRPC server stopped: {}\", e);",
+                "        }",
+                "    });",
+            ]
+            .join("\n"),
+        ]);
+        main.insert_stmts("main", "service", "app", &stmts).map_err(edit)?;
+    }
+    main.save(ctx)
+}
+
+fn generate_test(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    test::create_test_common(ctx, name)?;
+
+    let template = "grpc/test.rs.tpl";
+    let crate_name = crate_name(ctx).unwrap_or_else(|| name.to_string());
+    let content = render(ctx, template, name)?.replace("crate::", &format!("{}::", crate_name));
+    write_generated(ctx, &test_path(ctx, name), &content, Some(template))
+}
+
+/// Files `generate` writes for the entity, for `rvy destroy`.
+pub fn files(ctx: &Context, name: &str) -> Vec<String> {
+    vec![
+        proto_path(name),
+        server_path(ctx, name),
+        test_path(ctx, name),
+    ]
+}
+
+/// The entity's `mod` line in the handler mod.rs, for `rvy destroy`.
+pub fn module(ctx: &Context, name: &str) -> (String, String) {
+    (ctx.config.mod_file(Layer::Handler), grpc_module(name))
+}
+
+/// Undo `register_server`. The shared server goes with the last entity on it.
+pub fn remove_server(ctx: &Context, name: &str) -> Result<bool, RvyError> {
+    let main_path = "src/main.rs";
+    if !ctx.fs.exists(Path::new(main_path)) {
+        return Ok(false);
+    }
+
+    let naming = ctx.naming(name);
+    let grpc_mod = format!("{}::{}", ctx.config.module_path(Layer::Handler), grpc_module(&naming.snake));
+    let grpc_ty = format!("{}Grpc", naming.pascal);
+
+    let mut main = RustFile::read(&*ctx.fs, main_path)?;
+    let edit = |e| RvyError::edit(main_path, e);
+    let mut changed = main.remove_use(&grpc_mod).map_err(edit)?;
+    if main.has_fn("main") && main.has_local("main", "grpc").map_err(edit)? {
+        changed |= main
+            .remove_chain_call("main", "grpc", |call| {
+                call.method == "add_service" && call.args.first().is_some_and(|arg| calls_path(arg, &[&grpc_ty, "server"]))
+            })
+            .map_err(edit)?;
+        if !main.chain_methods("main", "grpc").map_err(edit)?.iter().any(|method| method == "add_service") {
+            main.remove_stmt_containing("main", "grpc.serve").map_err(edit)?;
+            main.remove_local("main", "grpc").map_err(edit)?;
+            main.remove_local("main", "grpc_addr").map_err(edit)?;
+        }
+    }
+    if changed {
+        main.save(ctx)?;
+    }
+    Ok(changed)
+}
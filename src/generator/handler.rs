@@ -1,278 +1,219 @@
+use crate::config::{Layer, WebFramework};
 use crate::context::Context;
-use crate::generator::{render, write_file, update_module_exports, to_snake_case, to_pascal_case};
-use std::fs;
+use crate::error::RvyError;
+use crate::generator::auth;
+use crate::generator::cargo::{self, dep};
+use crate::generator::rust_file::{calls_path, method_chain, RustFile};
+use crate::generator::{render, write_rendered, update_module_exports};
+use std::path::Path;
+use syn::Expr;
 
-pub fn generate(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/handler.rs.tpl");
+pub fn generate(ctx: &Context, name: &str) -> Result<(), RvyError> {
 
-    let content = render(template, name);
+    let config = &ctx.config;
+    let path = config.file_path(Layer::Handler, name);
 
-    let path = format!("src/handler/{}_handler.rs", name);
+    let mut deps = match config.web {
+        WebFramework::Axum => vec![
+            cargo::web_crate(config.web),
+            dep("serde_json", "1.0"),
+            dep("utoipa", "5").features(&["axum_extras"]),
+            dep("tokio", "1").features(&["full"]),
+        ],
+        WebFramework::Actix => vec![
+            cargo::web_crate(config.web),
+            dep("serde_json", "1.0"),
+            dep("utoipa", "5").features(&["actix_extras"]),
+        ],
+    };
+    if ctx.is_new_all {
+        // The server main serves the docs and reads .env
+        let swagger_feature = match config.web {
+            WebFramework::Axum => "axum",
+            WebFramework::Actix => "actix-web",
+        };
+        deps.push(dep("utoipa-swagger-ui", "8").features(&[swagger_feature]));
+        deps.push(dep("dotenvy", "0.15"));
+    }
+    cargo::require(ctx, &deps)?;
+    auth::generate_auth_module(ctx, name)?;
+    check_permission_support(ctx)?;
+    write_rendered(ctx, &path, &config.web.template("handler.rs.tpl"), name)?;
+    update_module_exports(ctx, &config.mod_file(Layer::Handler), &config.module_name(Layer::Handler, name))?;
+    update_main_router(ctx, name)
+}
 
-    write_file(ctx, &path, &content);
-    update_module_exports(ctx, "src/handler/mod.rs", &format!("{}_handler", name));
-    update_main_router(ctx, name);
+// Projects generated before permissions lack `Claims::require` and
+// `AppError::Forbidden`, which handlers with permissions call
+fn check_permission_support(ctx: &Context) -> Result<(), RvyError> {
+    if ctx.permissions.all().is_empty() {
+        return Ok(());
+    }
+    for (path, needle, missing) in [
+        ("src/auth.rs", "fn require", "Claims::require"),
+        ("src/error.rs", "Forbidden(", "AppError::Forbidden"),
+    ] {
+        if !ctx.fs.exists(Path::new(path)) {
+            continue;
+        }
+        let source = ctx.fs.read(Path::new(path)).map_err(|e| RvyError::io(path, e))?;
+        if !source.contains(needle) {
+            ctx.report.warning(
+                Some(path),
+                &format!("no {}, add it from rvy's template for the permission checks to compile", missing),
+            );
+        }
+    }
+    Ok(())
 }
 
-fn update_main_router(ctx: &Context, name: &str) {
+fn update_main_router(ctx: &Context, name: &str) -> Result<(), RvyError> {
     let main_path = "src/main.rs";
     
-    if ctx.dry_run {
-        println!("Would update {} with {} routes", main_path, name);
-        return;
+    if !ctx.fs.exists(Path::new(main_path)) {
+        return Ok(());
     }
     
-    let Ok(content) = fs::read_to_string(main_path) else {
-        return;
-    };
-    
-    let snake = to_snake_case(name);
-    let pascal = to_pascal_case(name);
+    // Replaces the project's welcome main, if it's still there
+    let template = if ctx.is_new_all { "main/server.rs.tpl" } else { "main/stub.rs.tpl" };
+    let new_main = render(ctx, &ctx.config.web.template(template), name)?;
     
-    // Check if handler is already imported
-    if content.contains(&format!("use handler::{}_handler", snake)) {
-        return; // Already added
+    let mut main = RustFile::read(&*ctx.fs, main_path)?;
+    if add_routes(ctx, &mut main, name, &new_main).map_err(|e| RvyError::edit(main_path, e))? {
+        main.save(ctx)?;
+    }
+    Ok(())
+}
+
+// Returns false when the handler is already wired into main.rs
+fn add_routes(ctx: &Context, main: &mut RustFile, name: &str, new_main: &str) -> Result<bool, String> {
+    let naming = ctx.naming(name);
+    let snake = naming.snake;
+    let pascal = naming.pascal;
+
+    // Module paths and type names follow rvy.toml
+    let config = &ctx.config;
+    let entity_mod = |layer| format!("{}::{}", config.module_path(layer), config.module_name(layer, &snake));
+    let handler_mod = entity_mod(Layer::Handler);
+    let handler_ty = config.type_name(Layer::Handler, &pascal);
+    let api_doc = format!("{}ApiDoc", pascal);
+    
+    if main.has_use(&handler_mod) {
+        return Ok(false);
     }
     
-    let mut new_content = content.clone();
+    // Top-level modules the generated main refers to
+    for layer in [Layer::Handler, Layer::Config, Layer::Factory, Layer::Usecase, Layer::Service] {
+        let module_path = config.module_path(layer);
+        let root = module_path.split("::").next().unwrap_or_default();
+        if !main.has_mod(root) {
+            main.add_mod(&format!("mod {};", root), None)?;
+        }
+    }
     
-    // Add import after handler module declaration
-    if !content.contains("use handler::") {
-        // First handler - add imports section
-        let imports = if ctx.is_new_all {
-            // gen-all: Add all necessary imports including Swagger UI
-            format!("mod handler;\n\nuse axum::Router;\nuse std::sync::Arc;\nuse tokio::net::TcpListener;\nuse utoipa::OpenApi;\nuse utoipa_swagger_ui::SwaggerUi;\n\nuse config::database::DatabaseConfig;\nuse factory::{}_factory;\nuse service::{}_service::{}Service;\nuse usecase::{}_usecase::{}Usecase;\nuse handler::{}_handler::{{{}Handler, {}ApiDoc}};", 
-                snake, snake, pascal, snake, pascal, snake, pascal, pascal)
-        } else {
-            // gen handler: Just handler import with Swagger
-            format!("mod handler;\n\nuse axum::Router;\nuse std::sync::Arc;\nuse tokio::net::TcpListener;\nuse utoipa::OpenApi;\nuse utoipa_swagger_ui::SwaggerUi;\nuse handler::{}_handler::{{{}Handler, {}ApiDoc}};", snake, pascal, pascal)
-        };
-        
-        new_content = new_content.replace("mod handler;", &imports);
-    } else {
-        // Add to existing imports - update to include ApiDoc and other dependencies
-        let import_line = format!("use handler::{}_handler::{{{}Handler, {}ApiDoc}};", snake, pascal, pascal);
-        if let Some(pos) = new_content.find("use handler::") {
-            // Find the last handler import line
-            let mut last_import_end = pos;
-            let mut search_from = pos;
-            while let Some(next_pos) = new_content[search_from..].find("use handler::") {
-                let actual_pos = search_from + next_pos;
-                if let Some(end) = new_content[actual_pos..].find('\n') {
-                    last_import_end = actual_pos + end + 1;
-                    search_from = last_import_end;
-                } else {
-                    break;
-                }
-            }
-            new_content.insert_str(last_import_end, &format!("{}\n", import_line));
+    let imports: &[&str] = match (config.web, ctx.is_new_all) {
+        (WebFramework::Axum, true) => {
+            &["axum::Router", "std::sync::Arc", "tokio::net::TcpListener", "utoipa::OpenApi", "utoipa_swagger_ui::SwaggerUi"]
         }
-        
-        // Add factory, service, usecase imports if doing new-all
-        if ctx.is_new_all {
-            // Add after handler imports if they don't exist yet
-            if !new_content.contains(&format!("use factory::{}_factory", snake)) {
-                if let Some(last_import) = new_content.rfind("use handler::") {
-                    if let Some(end) = new_content[last_import..].find('\n') {
-                        let insert_at = last_import + end + 1;
-                        new_content.insert_str(insert_at, &format!("use factory::{}_factory;\n", snake));
-                    }
-                }
-            }
-            if !new_content.contains(&format!("use service::{}_service", snake)) {
-                if let Some(last_import) = new_content.rfind("use factory::") {
-                    if let Some(end) = new_content[last_import..].find('\n') {
-                        let insert_at = last_import + end + 1;
-                        new_content.insert_str(insert_at, &format!("use service::{}_service::{}Service;\n", snake, pascal));
-                    }
-                }
-            }
-            if !new_content.contains(&format!("use usecase::{}_usecase", snake)) {
-                if let Some(last_import) = new_content.rfind("use service::") {
-                    if let Some(end) = new_content[last_import..].find('\n') {
-                        let insert_at = last_import + end + 1;
-                        new_content.insert_str(insert_at, &format!("use usecase::{}_usecase::{}Usecase;\n", snake, pascal));
-                    }
-                }
-            }
+        (WebFramework::Axum, false) => &["axum::Router", "tokio::net::TcpListener"],
+        (WebFramework::Actix, true) => {
+            &["actix_web::App", "actix_web::HttpServer", "std::sync::Arc", "utoipa::OpenApi", "utoipa_swagger_ui::SwaggerUi"]
+        }
+        (WebFramework::Actix, false) => &["actix_web::App", "actix_web::HttpServer"],
+    };
+    for import in imports {
+        if !main.has_use(import) {
+            main.add_use(&format!("use {};", import))?;
         }
     }
+    main.add_use(&format!("use {}::{{{}, {}}};", handler_mod, handler_ty, api_doc))?;
     
-    // Replace or update the main function
-    if content.contains("println!(\"🚀 Welcome") {
-        // First handler - generate complete main function
-        let new_main = if ctx.is_new_all {
-            // gen-all: Generate complete working code with Swagger UI
-            format!(r#"
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {{
-    // Load environment variables from .env file
-    dotenvy::dotenv().ok();
-    
-    println!("🚀 Starting API Server...");
-    
-    // Initialize services
-    let config = config::database::DatabaseConfig::from_env();
-    let repository = factory::{}_factory::create_{}_repository(&config).await?;
-    let usecase = Arc::new(usecase::{}_usecase::{}Usecase::new(repository));
-    let service = Arc::new(service::{}_service::{}Service::new(usecase));
-    
-    // Merge OpenAPI docs
-    let openapi = {}ApiDoc::openapi();
-    
-    let app = Router::new()
-        .merge({}Handler::routes(service))
-        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", openapi));
-    
-    let addr = "127.0.0.1:3000";
-    println!("✅ Server listening on http://{{}}", addr);
-    println!("📚 Swagger UI available at http://{{}}/swagger-ui", addr);
-    
-    let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
-    
-    Ok(())
-}}
-"#, snake, snake, snake, pascal, snake, pascal, pascal, pascal)
-        } else {
-            // gen handler: Generate with TODO comments
-            format!(r#"
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {{
-    println!("🚀 Starting API Server...");
+    // The project's welcome main has no bindings yet: replace it entirely
+    if !main.has_locals("main")? {
+        main.replace_fn("main", new_main)?;
+        return Ok(true);
+    }
     
-    // TODO: Initialize your services here
-    // Example:
-    // let config = config::database::DatabaseConfig::from_env();
-    // let repository = factory::{}_factory::create_{}_repository(&config).await?;
-    // let usecase = Arc::new(usecase::{}_usecase::{}Usecase::new(repository));
-    // let service = Arc::new(service::{}_service::{}Service::new(usecase));
+    if !ctx.is_new_all {
+        return Ok(true);
+    }
     
-    let app = Router::new();
-        // .merge({}Handler::routes(service));
+    // Additional handler: build its service next to the existing ones
+    let factory_mod = entity_mod(Layer::Factory);
+    let usecase = format!("{}::{}", entity_mod(Layer::Usecase), config.type_name(Layer::Usecase, &pascal));
+    let service = format!("{}::{}", entity_mod(Layer::Service), config.type_name(Layer::Service, &pascal));
+    let mut stmts = Vec::new();
+    if !main.has_local("main", "config")? {
+        stmts.push(format!("let config = {}::database::DatabaseConfig::from_env();", config.module_path(Layer::Config)));
+    }
+    stmts.push(format!("let repository_{} = {}::create_{}_repository(&config).await?;", snake, factory_mod, snake));
+    stmts.push(format!("let usecase_{} = Arc::new({}::new(repository_{}));", snake, usecase, snake));
+    stmts.push(format!("let service_{} = Arc::new({}::new(usecase_{}));", snake, service, snake));
+    if !main.has_local("main", "auth")? {
+        stmts.push("let auth = Arc::new(auth::AuthConfig::from_env()?);".to_string());
+    }
+    main.insert_stmts("main", "service", "app", &stmts)?;
     
-    let addr = "127.0.0.1:3000";
-    println!("✅ Server listening on http://{{}}", addr);
+    // actix builds the app once per worker, so each gets its own handle on the service
+    let (mount, service_args) = match config.web {
+        WebFramework::Axum => ("merge", format!("service_{}, auth.clone()", snake)),
+        WebFramework::Actix => ("configure", format!("service_{}.clone(), auth.clone()", snake)),
+    };
+    main.add_chain_call(
+        "main",
+        "app",
+        mount,
+        &format!("{}::routes({})", handler_ty, service_args),
+        |arg| calls_path(arg, &["routes"]),
+    )?;
+    
+    let swagger_url = format!(".url(\"/api-docs/{}.json\", {}::openapi())", snake, api_doc);
+    let is_swagger = |arg: &Expr| calls_path(method_chain(arg).1, &["SwaggerUi", "new"]);
+    if !main.extend_chain_arg("main", "app", is_swagger, &swagger_url)? {
+        ctx.report.warning(Some("src/main.rs"), &format!("no SwaggerUi, skipping API docs for {}", name));
+    }
     
-    let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    Ok(true)
+}
+
+/// Undo `add_routes`: drop the entity's import, services, route merge and
+/// API docs from main.rs. Returns whether main.rs had any of them.
+pub fn remove_routes(ctx: &Context, name: &str) -> Result<bool, RvyError> {
+    let main_path = "src/main.rs";
+    if !ctx.fs.exists(Path::new(main_path)) {
+        return Ok(false);
+    }
     
-    Ok(())
-}}
-"#, snake, snake, snake, pascal, snake, pascal, pascal)
-        };
-        
-        // Find and replace main function
-        if let Some(start) = new_content.find("#[tokio::main]") {
-            if let Some(end) = new_content[start..].find("\n}\n") {
-                new_content.replace_range(start..start + end + 2, &new_main);
-            }
-        }
-    } else if ctx.is_new_all {
-        // Additional handler - merge with existing handlers
-        // Add service initialization
-        let service_init = format!("    let repository_{} = factory::{}_factory::create_{}_repository(&config).await?;\n    let usecase_{} = Arc::new(usecase::{}_usecase::{}Usecase::new(repository_{}));\n    let service_{} = Arc::new(service::{}_service::{}Service::new(usecase_{}));",
-            snake, snake, snake, snake, snake, pascal, snake, snake, snake, pascal, snake);
-        
-        // Find where to insert service initialization (after last service)
-        if let Some(pos) = new_content.find("let service") {
-            // Find last service declaration
-            let mut last_service_end = pos;
-            let mut search_from = pos;
-            while let Some(next) = new_content[search_from..].find("let service") {
-                let actual_pos = search_from + next;
-                if let Some(end) = new_content[actual_pos..].find(";\n") {
-                    last_service_end = actual_pos + end + 2;
-                    search_from = last_service_end;
-                } else {
-                    break;
-                }
-            }
-            new_content.insert_str(last_service_end, &format!("\n{}\n", service_init));
-        }
-        
-        // Add additional OpenAPI doc variable
-        if let Some(pos) = new_content.find("let openapi = ") {
-            if let Some(end_pos) = new_content[pos..].find("ApiDoc::openapi();") {
-                let line_end = pos + end_pos + "ApiDoc::openapi();".len();
-                // Add new openapi variable after existing one
-                new_content.insert_str(line_end, &format!("\n    let openapi_{} = {}ApiDoc::openapi();", snake, pascal));
-            }
-        }
-        
-        // Update SwaggerUi to use multiple OpenAPI specs by creating combined docs
-        // We need to merge at the Swagger UI level, not at OpenAPI level
-        // For now, we'll just show the latest one (limitation of utoipa-swagger-ui 8.x)
-        if let Some(swagger_start) = new_content.find(".merge(SwaggerUi::new(\"/swagger-ui\").url(\"/api-docs/openapi.json\", openapi))") {
-            // Replace with multiple URL endpoints
-            let swagger_end = swagger_start + ".merge(SwaggerUi::new(\"/swagger-ui\").url(\"/api-docs/openapi.json\", openapi))".len();
-            
-            // Collect all ApiDoc names
-            let mut api_docs = Vec::new();
-            let mut search_from = 0;
-            while let Some(pos) = new_content[search_from..].find("let openapi") {
-                let actual_pos = search_from + pos;
-                if let Some(equals) = new_content[actual_pos..].find(" = ") {
-                    let var_start = actual_pos + "let ".len();
-                    let var_end = actual_pos + equals;
-                    let var_name = &new_content[var_start..var_end];
-                    if var_name.starts_with("openapi") {
-                        api_docs.push(var_name.to_string());
-                    }
-                }
-                search_from = actual_pos + 1;
-            }
-            
-            if api_docs.len() > 1 {
-                // Build multiple URL calls for SwaggerUI
-                let urls: Vec<String> = api_docs.iter().enumerate().map(|(_i, doc_var)| {
-                    let name = if doc_var == "openapi" {
-                        // First one without suffix - need to extract from earlier in code
-                        // Look for "ProductApiDoc::openapi()" pattern
-                        if let Some(pos) = new_content.find(&format!("{} = ", doc_var)) {
-                            if let Some(api_start) = new_content[pos..].find("ApiDoc::openapi()") {
-                                let before = &new_content[pos..pos + api_start];
-                                if let Some(_name_start) = before.rfind(char::is_alphabetic) {
-                                    let full_before = &new_content[..pos + api_start];
-                                    if let Some(name_start_pos) = full_before.rfind(char::is_uppercase) {
-                                        let entity_name = &new_content[name_start_pos..pos + api_start];
-                                        entity_name.replace("ApiDoc", "").to_lowercase()
-                                    } else {
-                                        "api".to_string()
-                                    }
-                                } else {
-                                    "api".to_string()
-                                }
-                            } else {
-                                "api".to_string()
-                            }
-                        } else {
-                            "api".to_string()
-                        }
-                    } else {
-                        doc_var.replace("openapi_", "")
-                    };
-                    format!(".url(\"/api-docs/{}.json\", {})", name, doc_var)
-                }).collect();
-                
-                let new_swagger = format!(".merge(SwaggerUi::new(\"/swagger-ui\"){})", urls.join(""));
-                new_content.replace_range(swagger_start..swagger_end, &new_swagger);
-            }
+    let naming = ctx.naming(name);
+    let snake = naming.snake;
+    let config = &ctx.config;
+    let handler_mod = format!("{}::{}", config.module_path(Layer::Handler), config.module_name(Layer::Handler, &snake));
+    let handler_ty = config.type_name(Layer::Handler, &naming.pascal);
+    let api_doc = format!("{}ApiDoc", naming.pascal);
+    
+    let mut main = RustFile::read(&*ctx.fs, main_path)?;
+    let changed = unwire(&mut main, &snake, &handler_mod, &handler_ty, &api_doc)
+        .map_err(|e| RvyError::edit(main_path, e))?;
+    if changed {
+        main.save(ctx)?;
+    }
+    Ok(changed)
+}
+
+fn unwire(main: &mut RustFile, snake: &str, handler_mod: &str, handler_ty: &str, api_doc: &str) -> Result<bool, String> {
+    let mut changed = main.remove_use(handler_mod)?;
+    if main.has_fn("main") {
+        for var in ["repository", "usecase", "service"] {
+            changed |= main.remove_local("main", &format!("{}_{}", var, snake))?;
         }
-        
-        // Merge routes - find the last route merge before SwaggerUi
-        if let Some(app_pos) = new_content.find("let app = Router::new()") {
-            if let Some(swagger_pos) = new_content[app_pos..].find(".merge(SwaggerUi::") {
-                let insert_at = app_pos + swagger_pos;
-                // Insert route before SwaggerUi with proper indentation
-                new_content.insert_str(insert_at, &format!(".merge({}Handler::routes(service_{}))\n        ", pascal, snake));
-            }
+        if main.has_local("main", "app")? {
+            changed |= main.remove_chain_call("main", "app", |call| {
+                (call.method == "merge" || call.method == "configure") && call.args.first().is_some_and(|arg| calls_path(arg, &[handler_ty, "routes"]))
+            })?;
+            changed |= main.remove_chain_call("main", "app", |call| {
+                call.method == "url" && call.args.iter().nth(1).is_some_and(|arg| calls_path(arg, &[api_doc, "openapi"]))
+            })?;
         }
     }
-    
-    if let Err(e) = fs::write(main_path, new_content) {
-        eprintln!("Warning: Failed to update {}: {}", main_path, e);
-    } else {
-        println!("Updated {} with {} routes", main_path, name);
-    }
+    Ok(changed)
 }
@@ -0,0 +1,256 @@
+// `.rvy/manifest.json`: every file rvy generated, with the hash of what was
+// written, so regeneration can tell untouched files from hand-edited ones.
+//
+// {
+//   "version": 1,
+//   "files": {
+//     "src/handler/user_handler.rs": {
+//       "hash": "sha256:...",
+//       "template": "handler.rs.tpl",
+//       "template_hash": "sha256:...",
+//       "rvy_version": "0.3.0"
+//     }
+//   },
+//   "entities": {
+//     "user": { "fields": [{ "name": "email", "type": "string" }] }
+//   }
+// }
+//
+// `entities` holds the fields each entity was last generated with, so
+// `rvy regen` can render it again without --field or rvy.entities.toml.
+//
+// A copy of each file as generated is kept under `.rvy/base/` as the common
+// ancestor for `rvy regen`'s three-way merge.
+//
+// The manifest belongs to the crate a file is in (the nearest directory with
+// a Cargo.toml), and its paths are relative to that crate.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::RvyError;
+use crate::generator::field::Field;
+use crate::generator::template;
+use crate::schema::FieldDef;
+use crate::vfs::FileSystem;
+
+pub const MANIFEST_FILE: &str = ".rvy/manifest.json";
+
+pub const BASE_DIR: &str = ".rvy/base";
+
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    #[serde(default)]
+    files: BTreeMap<String, Entry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    entities: BTreeMap<String, EntityEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EntityEntry {
+    fields: Vec<FieldDef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template_hash: Option<String>,
+    rvy_version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileState {
+    /// Not in the manifest: written by hand or before manifests existed
+    Untracked,
+    /// Exactly what rvy last wrote
+    Unmodified,
+    /// Changed since rvy wrote it
+    Modified,
+}
+
+pub fn hash(content: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256:{}", hex)
+}
+
+/// How `content`, the current content of `path`, relates to what rvy wrote.
+pub fn state(fs: &dyn FileSystem, path: &str, content: &str) -> Result<FileState, RvyError> {
+    let Some((root, key)) = locate(fs, path) else {
+        return Ok(FileState::Untracked);
+    };
+    Ok(match load(fs, &root)?.files.get(&key) {
+        None => FileState::Untracked,
+        Some(entry) if entry.hash == hash(content) => FileState::Unmodified,
+        Some(_) => FileState::Modified,
+    })
+}
+
+/// Record that rvy wrote `content` to `path`, rendered from `template`.
+pub fn record(fs: &dyn FileSystem, path: &str, content: &str, template: Option<&str>) -> Result<(), RvyError> {
+    let template_hash = template.map(template::source).transpose()?.flatten().map(|source| hash(&source));
+    if let Some((root, key)) = locate(fs, path) {
+        write_base(fs, &root, &key, Some(content))?;
+    }
+    update(fs, path, |files, key| {
+        files.insert(
+            key,
+            Entry {
+                hash: hash(content),
+                template: template.map(str::to_string),
+                template_hash,
+                rvy_version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+        );
+    })
+}
+
+/// What rvy last generated for `path`, if it kept a copy.
+pub fn base(fs: &dyn FileSystem, path: &str) -> Result<Option<String>, RvyError> {
+    let Some((root, key)) = locate(fs, path) else {
+        return Ok(None);
+    };
+    let file = root.join(BASE_DIR).join(&key);
+    if !fs.exists(&file) {
+        return Ok(None);
+    }
+    fs.read(&file).map(Some).map_err(|e| RvyError::Manifest(format!("cannot read {}: {}", file.display(), e)))
+}
+
+/// After rvy edited a generated file in place (a new `pub mod`, appended
+/// tests), make the edit part of what rvy generated, so the file stays
+/// unmodified and `rvy regen` can update it. Only applies when the file was
+/// unmodified before the edit; otherwise user edits would become the base.
+pub fn refresh(fs: &dyn FileSystem, path: &str, before: &str, after: &str) -> Result<(), RvyError> {
+    if state(fs, path, before)? != FileState::Unmodified {
+        return Ok(());
+    }
+    if let Some((root, key)) = locate(fs, path) {
+        write_base(fs, &root, &key, Some(after))?;
+    }
+    update(fs, path, |files, key| {
+        if let Some(entry) = files.get_mut(&key) {
+            entry.hash = hash(after);
+        }
+    })
+}
+
+/// Drop a removed file from the manifest.
+pub fn forget(fs: &dyn FileSystem, path: &str) -> Result<(), RvyError> {
+    if let Some((root, key)) = locate(fs, path) {
+        write_base(fs, &root, &key, None)?;
+    }
+    update(fs, path, |files, key| {
+        files.remove(&key);
+    })
+}
+
+/// Record the fields entity `name` was generated with.
+pub fn record_entity(fs: &dyn FileSystem, name: &str, fields: &[Field]) -> Result<(), RvyError> {
+    let Some(root) = crate_root(fs) else {
+        return Ok(());
+    };
+    let mut manifest = load(fs, &root)?;
+    let fields: Vec<FieldDef> = fields.iter().map(FieldDef::from_field).collect();
+    if manifest.entities.get(name).is_some_and(|entry| entry.fields == fields) {
+        return Ok(());
+    }
+    manifest.entities.insert(name.to_string(), EntityEntry { fields });
+    save(fs, &root, &manifest)
+}
+
+/// The fields entity `name` was last generated with, if rvy recorded them.
+pub fn entity_fields(fs: &dyn FileSystem, name: &str) -> Result<Option<Vec<Field>>, RvyError> {
+    let Some(root) = crate_root(fs) else {
+        return Ok(None);
+    };
+    let manifest = load(fs, &root)?;
+    let Some(entry) = manifest.entities.get(name) else {
+        return Ok(None);
+    };
+    entry
+        .fields
+        .iter()
+        .map(FieldDef::to_field)
+        .collect::<Result<Vec<_>, String>>()
+        .map(Some)
+        .map_err(|e| RvyError::Manifest(format!("entity '{}' in {}: {}", name, MANIFEST_FILE, e)))
+}
+
+/// Drop a destroyed entity's fields.
+pub fn forget_entity(fs: &dyn FileSystem, name: &str) -> Result<(), RvyError> {
+    let Some(root) = crate_root(fs) else {
+        return Ok(());
+    };
+    let mut manifest = load(fs, &root)?;
+    if manifest.entities.remove(name).is_some() {
+        save(fs, &root, &manifest)?;
+    }
+    Ok(())
+}
+
+fn update(
+    fs: &dyn FileSystem,
+    path: &str,
+    change: impl FnOnce(&mut BTreeMap<String, Entry>, String),
+) -> Result<(), RvyError> {
+    let Some((root, key)) = locate(fs, path) else {
+        return Ok(());
+    };
+    let mut manifest = load(fs, &root)?;
+    change(&mut manifest.files, key);
+    save(fs, &root, &manifest)
+}
+
+// The crate root that owns `path` and the path relative to it
+fn locate(fs: &dyn FileSystem, path: &str) -> Option<(PathBuf, String)> {
+    let path = Path::new(path);
+    let root = path.ancestors().skip(1).find(|dir| fs.exists(&dir.join("Cargo.toml")))?;
+    let key = path.strip_prefix(root).ok()?;
+    let key = key.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+    Some((root.to_path_buf(), key))
+}
+
+// The crate root generators work in, which owns the entity records
+fn crate_root(fs: &dyn FileSystem) -> Option<PathBuf> {
+    locate(fs, MANIFEST_FILE).map(|(root, _)| root)
+}
+
+// Store (or with `None`, delete) the base copy of `key`
+fn write_base(fs: &dyn FileSystem, root: &Path, key: &str, content: Option<&str>) -> Result<(), RvyError> {
+    let file = root.join(BASE_DIR).join(key);
+    match content {
+        Some(content) => fs.write(&file, content).map_err(|e| RvyError::Manifest(format!("cannot write {}: {}", file.display(), e))),
+        None if fs.exists(&file) => fs.remove(&file).map_err(|e| RvyError::Manifest(format!("cannot remove {}: {}", file.display(), e))),
+        None => Ok(()),
+    }
+}
+
+fn load(fs: &dyn FileSystem, root: &Path) -> Result<Manifest, RvyError> {
+    let path = root.join(MANIFEST_FILE);
+    if !fs.exists(&path) {
+        return Ok(Manifest { version: MANIFEST_VERSION, files: BTreeMap::new(), entities: BTreeMap::new() });
+    }
+    let content = fs.read(&path).map_err(|e| RvyError::Manifest(format!("cannot read {}: {}", path.display(), e)))?;
+    let manifest: Manifest =
+        serde_json::from_str(&content).map_err(|e| RvyError::Manifest(format!("invalid {}: {}", path.display(), e)))?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(RvyError::Manifest(format!("{} was written by a newer rvy (version {})", path.display(), manifest.version)));
+    }
+    Ok(manifest)
+}
+
+fn save(fs: &dyn FileSystem, root: &Path, manifest: &Manifest) -> Result<(), RvyError> {
+    let path = root.join(MANIFEST_FILE);
+    let json = serde_json::to_string_pretty(manifest).expect("manifest serializes");
+    fs.write(&path, &(json + "\n")).map_err(|e| RvyError::Manifest(format!("cannot write {}: {}", path.display(), e)))
+}
@@ -0,0 +1,99 @@
+// Line-based three-way merge (diff3) of a generated file.
+//
+// `base` is what rvy generated last time, `current` the file as it is now
+// and `generated` the new template output. Lines only one side changed are
+// taken from that side; lines both changed differently become a conflict
+// with the usual markers.
+
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+const CURRENT_MARKER: &str = "<<<<<<< current";
+const SEPARATOR_MARKER: &str = "=======";
+const GENERATED_MARKER: &str = ">>>>>>> generated";
+
+pub struct Merged {
+    pub content: String,
+    pub conflicts: usize,
+}
+
+pub fn merge3(base: &str, current: &str, generated: &str) -> Merged {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = current.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = generated.split_inclusive('\n').collect();
+
+    // For each base line, the line it matches on each side (if unchanged)
+    let ours_match = matches(&base, &ours);
+    let theirs_match = matches(&base, &theirs);
+    let stable = |b: usize, o: usize, t: usize| {
+        b < base.len() && ours_match[b] == Some(o) && theirs_match[b] == Some(t)
+    };
+
+    let mut out = String::new();
+    let mut conflicts = 0;
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    loop {
+        // Lines unchanged on both sides
+        while stable(b, o, t) {
+            out.push_str(base[b]);
+            b += 1;
+            o += 1;
+            t += 1;
+        }
+        if b == base.len() && o == ours.len() && t == theirs.len() {
+            break;
+        }
+
+        // The changed region runs up to the next base line both sides kept
+        let next = (b..base.len()).find(|&k| ours_match[k].is_some() && theirs_match[k].is_some());
+        let (b_end, o_end, t_end) = match next {
+            Some(k) => (k, ours_match[k].unwrap(), theirs_match[k].unwrap()),
+            None => (base.len(), ours.len(), theirs.len()),
+        };
+        let base_chunk = &base[b..b_end];
+        let ours_chunk = &ours[o..o_end];
+        let theirs_chunk = &theirs[t..t_end];
+
+        if ours_chunk == base_chunk || ours_chunk == theirs_chunk {
+            push_lines(&mut out, theirs_chunk);
+        } else if theirs_chunk == base_chunk {
+            push_lines(&mut out, ours_chunk);
+        } else {
+            conflicts += 1;
+            push_marker(&mut out, CURRENT_MARKER);
+            push_lines(&mut out, ours_chunk);
+            push_marker(&mut out, SEPARATOR_MARKER);
+            push_lines(&mut out, theirs_chunk);
+            push_marker(&mut out, GENERATED_MARKER);
+        }
+        (b, o, t) = (b_end, o_end, t_end);
+    }
+
+    Merged { content: out, conflicts }
+}
+
+fn matches(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut matched = vec![None; base.len()];
+    for op in capture_diff_slices(Algorithm::Myers, base, other) {
+        if let DiffOp::Equal { old_index, new_index, len } = op {
+            for i in 0..len {
+                matched[old_index + i] = Some(new_index + i);
+            }
+        }
+    }
+    matched
+}
+
+fn push_lines(out: &mut String, lines: &[&str]) {
+    for line in lines {
+        out.push_str(line);
+    }
+}
+
+// Markers go on their own line, even after a last line without a newline
+fn push_marker(out: &mut String, marker: &str) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(marker);
+    out.push('\n');
+}
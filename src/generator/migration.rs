@@ -1,136 +1,129 @@
 use crate::context::Context;
-use std::fs;
-use std::io::Write;
+use crate::error::RvyError;
+use crate::generator::cargo::{self, dep};
+use crate::generator::{manifest, overwrite_allowed, render};
 use std::path::Path;
 
-pub fn generate_postgres_migration(ctx: &Context, name: &str) -> std::io::Result<()> {
-    let template = include_str!("../../templates/migrations/postgres_create_table.sql.tpl");
-    let content = template.replace("{{name}}", name);
+pub fn generate_postgres_migration(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let template = "migrations/postgres_create_table.sql.tpl";
+    let content = render(ctx, template, name)?;
     
-    let migrations_dir = Path::new("migrations");
-    fs::create_dir_all(migrations_dir)?;
+    let migrations_dir = Path::new(&ctx.config.migrations_dir);
     
     let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
-    let filename = format!("{}_create_{}s_table_postgres.sql", timestamp, name);
+    let filename = format!("{}_create_{}_table_postgres.sql", timestamp, ctx.naming(name).table);
     let file_path = migrations_dir.join(&filename);
     
-    if file_path.exists() && !ctx.force {
-        println!("⏭️  Skipping PostgreSQL migration (already exists): {}", filename);
+    if ctx.fs.exists(&file_path) && !ctx.force {
+        ctx.report.skipped(&file_path.to_string_lossy(), "already exists, use --force to overwrite");
         return Ok(());
     }
-    
-    if ctx.dry_run {
-        println!("🔍 Would generate PostgreSQL migration: {}", filename);
+
+    if ctx.fs.exists(&file_path) && !overwrite_allowed(ctx, &file_path.to_string_lossy())? {
         return Ok(());
     }
     
-    let mut file = fs::File::create(&file_path)?;
-    file.write_all(content.as_bytes())?;
+    ctx.fs.write(&file_path, &content).map_err(|e| RvyError::io(file_path.display(), e))?;
+    manifest::record(&*ctx.fs, &file_path.to_string_lossy(), &content, Some(template))?;
     
-    println!("✅ Generated PostgreSQL migration: {}", filename);
+    ctx.report.created(&file_path.to_string_lossy());
     Ok(())
 }
 
-pub fn generate_mysql_migration(ctx: &Context, name: &str) -> std::io::Result<()> {
-    let template = include_str!("../../templates/migrations/mysql_create_table.sql.tpl");
-    let content = template.replace("{{name}}", name);
+pub fn generate_mysql_migration(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let template = "migrations/mysql_create_table.sql.tpl";
+    let content = render(ctx, template, name)?;
     
-    let migrations_dir = Path::new("migrations");
-    fs::create_dir_all(migrations_dir)?;
+    let migrations_dir = Path::new(&ctx.config.migrations_dir);
     
     let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
-    let filename = format!("{}_create_{}s_table_mysql.sql", timestamp, name);
+    let filename = format!("{}_create_{}_table_mysql.sql", timestamp, ctx.naming(name).table);
     let file_path = migrations_dir.join(&filename);
     
-    if file_path.exists() && !ctx.force {
-        println!("⏭️  Skipping MySQL migration (already exists): {}", filename);
+    if ctx.fs.exists(&file_path) && !ctx.force {
+        ctx.report.skipped(&file_path.to_string_lossy(), "already exists, use --force to overwrite");
         return Ok(());
     }
-    
-    if ctx.dry_run {
-        println!("🔍 Would generate MySQL migration: {}", filename);
+
+    if ctx.fs.exists(&file_path) && !overwrite_allowed(ctx, &file_path.to_string_lossy())? {
         return Ok(());
     }
     
-    let mut file = fs::File::create(&file_path)?;
-    file.write_all(content.as_bytes())?;
+    ctx.fs.write(&file_path, &content).map_err(|e| RvyError::io(file_path.display(), e))?;
+    manifest::record(&*ctx.fs, &file_path.to_string_lossy(), &content, Some(template))?;
     
-    println!("✅ Generated MySQL migration: {}", filename);
+    ctx.report.created(&file_path.to_string_lossy());
     Ok(())
 }
 
-pub fn generate_sqlite_migration(ctx: &Context, name: &str) -> std::io::Result<()> {
-    let template = include_str!("../../templates/migrations/sqlite_create_table.sql.tpl");
-    let content = template.replace("{{name}}", name);
+pub fn generate_sqlite_migration(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let template = "migrations/sqlite_create_table.sql.tpl";
+    let content = render(ctx, template, name)?;
     
-    let migrations_dir = Path::new("migrations");
-    fs::create_dir_all(migrations_dir)?;
+    let migrations_dir = Path::new(&ctx.config.migrations_dir);
     
     let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
-    let filename = format!("{}_create_{}s_table_sqlite.sql", timestamp, name);
+    let filename = format!("{}_create_{}_table_sqlite.sql", timestamp, ctx.naming(name).table);
     let file_path = migrations_dir.join(&filename);
     
-    if file_path.exists() && !ctx.force {
-        println!("⏭️  Skipping SQLite migration (already exists): {}", filename);
+    if ctx.fs.exists(&file_path) && !ctx.force {
+        ctx.report.skipped(&file_path.to_string_lossy(), "already exists, use --force to overwrite");
         return Ok(());
     }
-    
-    if ctx.dry_run {
-        println!("🔍 Would generate SQLite migration: {}", filename);
+
+    if ctx.fs.exists(&file_path) && !overwrite_allowed(ctx, &file_path.to_string_lossy())? {
         return Ok(());
     }
     
-    let mut file = fs::File::create(&file_path)?;
-    file.write_all(content.as_bytes())?;
+    ctx.fs.write(&file_path, &content).map_err(|e| RvyError::io(file_path.display(), e))?;
+    manifest::record(&*ctx.fs, &file_path.to_string_lossy(), &content, Some(template))?;
     
-    println!("✅ Generated SQLite migration: {}", filename);
+    ctx.report.created(&file_path.to_string_lossy());
     Ok(())
 }
 
-pub fn generate_mongodb_setup(ctx: &Context, name: &str) -> std::io::Result<()> {
-    let template = include_str!("../../templates/migrations/mongodb_setup.rs.tpl");
-    let content = template.replace("{{name}}", name);
+pub fn generate_mongodb_setup(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    cargo::require(ctx, &[dep("mongodb", "3.1")])?;
+    let template = "migrations/mongodb_setup.rs.tpl";
+    let content = render(ctx, template, name)?;
     
-    let migrations_dir = Path::new("migrations");
-    fs::create_dir_all(migrations_dir)?;
+    let migrations_dir = Path::new(&ctx.config.migrations_dir);
     
-    let filename = format!("setup_{}s_collection.rs", name);
+    let filename = format!("setup_{}_collection.rs", ctx.naming(name).table);
     let file_path = migrations_dir.join(&filename);
     
-    if file_path.exists() && !ctx.force {
-        println!("⏭️  Skipping MongoDB setup (already exists): {}", filename);
+    if ctx.fs.exists(&file_path) && !ctx.force {
+        ctx.report.skipped(&file_path.to_string_lossy(), "already exists, use --force to overwrite");
         return Ok(());
     }
-    
-    if ctx.dry_run {
-        println!("🔍 Would generate MongoDB setup script: {}", filename);
+
+    if ctx.fs.exists(&file_path) && !overwrite_allowed(ctx, &file_path.to_string_lossy())? {
         return Ok(());
     }
     
-    let mut file = fs::File::create(&file_path)?;
-    file.write_all(content.as_bytes())?;
+    ctx.fs.write(&file_path, &content).map_err(|e| RvyError::io(file_path.display(), e))?;
+    manifest::record(&*ctx.fs, &file_path.to_string_lossy(), &content, Some(template))?;
     
-    println!("✅ Generated MongoDB setup script: {}", filename);
+    ctx.report.created(&file_path.to_string_lossy());
     Ok(())
 }
 
-pub fn generate_migration(ctx: &Context, name: &str, db_type: &str) -> std::io::Result<()> {
+pub fn generate_migration(ctx: &Context, name: &str, db_type: &str) -> Result<(), RvyError> {
     match db_type {
         "postgres" => generate_postgres_migration(ctx, name),
         "mysql" => generate_mysql_migration(ctx, name),
         "sqlite" => generate_sqlite_migration(ctx, name),
         "mongodb" => generate_mongodb_setup(ctx, name),
         "all" => {
-            generate_postgres_migration(ctx, name)?;
-            generate_mysql_migration(ctx, name)?;
-            generate_sqlite_migration(ctx, name)?;
-            generate_mongodb_setup(ctx, name)?;
+            for db_type in &ctx.databases {
+                generate_migration(ctx, name, db_type)?;
+            }
             Ok(())
         }
-        _ => {
-            eprintln!("❌ Unknown database type: {}", db_type);
-            eprintln!("   Supported types: postgres, mysql, sqlite, mongodb, all");
-            std::process::exit(1);
-        }
+        _ => Err(RvyError::Usage(format!(
+            "Unknown database type: {}\n   Supported types: postgres, mysql, sqlite, mongodb, all",
+            db_type
+        ))),
     }
 }
+
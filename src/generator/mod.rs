@@ -1,92 +1,277 @@
-use std::fs;
 use std::path::Path;
 
+use crate::config::{Layer, ProjectConfig};
 use crate::context::Context;
+use crate::error::RvyError;
+use crate::generator::field::{label_field, Field};
+use crate::generator::naming::humanize;
+use crate::generator::manifest::FileState;
+use crate::generator::rust_file::RustFile;
+use minijinja::context;
 
 pub mod service;
 pub mod usecase;
 pub mod repository;
 pub mod data;
 pub mod adapter;
+pub mod auth;
 pub mod handler;
+pub mod grpc;
+pub mod graphql;
 pub mod project;
 pub mod dispatch;
+pub mod destroy;
 pub mod test;
 pub mod migration;
 pub mod error;
+pub mod field;
+pub mod naming;
+pub mod rust_file;
+pub mod template;
+pub mod manifest;
+pub mod merge;
+pub mod cargo;
+pub mod diff;
 
+pub const DATABASES: &[&str] = &["postgres", "mysql", "mongodb", "sqlite"];
 
-pub fn render(template: &str, name: &str) -> String {
-    let pascal = to_pascal_case(name);
-    let snake = to_snake_case(name);
+/// Render an embedded template for entity `name` with the entity's fields
+/// and databases from `ctx`. Template errors (syntax, undefined variables)
+/// fail the command.
+pub fn render(ctx: &Context, template: &str, name: &str) -> Result<String, RvyError> {
+    let fields = if ctx.fields.is_empty() {
+        field::default_fields()
+    } else {
+        ctx.fields.clone()
+    };
+    let label = label_field(&fields)
+        .map(|f| f.name.clone())
+        .unwrap_or_else(|| "name".to_string());
+
+    let naming = ctx.naming(name);
+    let config = &ctx.config;
+    // Module of the entity's file in `layer`, relative to the crate root
+    let entity_mod = |layer| format!("{}::{}", config.module_path(layer), config.module_name(layer, &naming.snake));
+    let type_name = |layer| config.type_name(layer, &naming.pascal);
+
+    let vars = context! {
+        package_name => name,              // For Cargo.toml (allows hyphens)
+        name => naming.snake,              // For Rust code (user_profile)
+        Name => naming.pascal,             // UserProfile
+        name_camel => naming.camel,        // userProfile
+        name_kebab => naming.kebab,        // user-profile
+        NAME => naming.screaming,          // USER_PROFILE
+        names => naming.plural,            // user_profiles
+        Names => naming.plural_pascal(),   // UserProfiles
+        route => naming.route(),           // user-profiles
+        table => naming.table,             // user_profiles, unless overridden
+        fields => fields.iter().map(Field::to_value).collect::<Vec<_>>(),
+        label_field => label,
+        label_title => humanize(&label),
+        databases => ctx.databases,                        // Databases this entity targets
+        permissions => ctx.permissions,                    // Roles or scopes per operation (list, get, ...)
+        scopes => ctx.permissions.all(),                   // Every role or scope the entity's operations name
+        project_databases => config.code_databases(),      // Databases enabled in rvy.toml
+        has_sql => config.code_databases().iter().any(|db| db != "mongodb"),
+        kind => config.kind.name(),                        // api, worker, cli, lib or minimal
+        http => config.kind.http(),                        // Handlers, Swagger UI and the HTTP server
+        web => config.web.name(),                          // axum or actix
+        project_layers => project_modules(config),         // Top-level modules of the kind's layers
+        service_mod => entity_mod(Layer::Service),        // service::user_service
+        usecase_mod => entity_mod(Layer::Usecase),
+        repository_mod => entity_mod(Layer::Repository),
+        data_mod => entity_mod(Layer::Data),
+        handler_mod => entity_mod(Layer::Handler),
+        factory_mod => entity_mod(Layer::Factory),
+        adapter_mod => config.module_path(Layer::Adapter), // adapter
+        config_mod => config.module_path(Layer::Config),   // config
+        Service => type_name(Layer::Service),              // UserService
+        Usecase => type_name(Layer::Usecase),
+        Repository => type_name(Layer::Repository),
+        Data => type_name(Layer::Data),
+        Handler => type_name(Layer::Handler),
+        port => config.port,
+        grpc_port => config.grpc_port,
+        grpc_mod => format!("{}::{}_grpc", config.module_path(Layer::Handler), naming.snake), // handler::user_grpc
+        graphql_mod => format!("{}::{}_graphql", config.module_path(Layer::Handler), naming.snake), // handler::user_graphql
+        graphql_schema_mod => format!("{}::graphql", config.module_path(Layer::Handler)), // handler::graphql
+    };
 
-    template
-        .replace("{{package_name}}", name)  // For Cargo.toml (allows hyphens)
-        .replace("{{name}}", &snake)        // For Rust code (underscores only)
-        .replace("{{Name}}", &pascal)       // PascalCase
+    template::render(template, vars).map_err(|e| RvyError::Template(format!("{:#}", e)))
 }
 
-pub fn to_snake_case(s: &str) -> String {
-    s.replace('-', "_")
+// `mod` lines for a new project's main.rs and lib.rs
+fn project_modules(config: &ProjectConfig) -> Vec<String> {
+    let mut modules: Vec<String> = Vec::new();
+    for layer in config.kind.layers() {
+        let path = config.module_path(layer);
+        let root = path.split("::").next().unwrap_or_default().to_string();
+        if !modules.contains(&root) {
+            modules.push(root);
+        }
+    }
+    modules
 }
 
-pub fn to_pascal_case(s: &str) -> String {
-    s.split(|c| c == '_' || c == '-')
-        .map(|p| {
-            let mut c = p.chars();
-            match c.next() {
-                None => String::new(),
-                Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+/// Declare the top-level modules rvy generated in main.rs (`mod`) and lib.rs
+/// (`pub mod`): `error`, `auth` and every layer root that has a module file.
+pub fn declare_modules(ctx: &Context) -> Result<(), RvyError> {
+    let mut modules = vec!["error".to_string(), "auth".to_string()];
+    modules.extend(project_modules(&ctx.config));
+    modules.retain(|module| {
+        ctx.fs.exists(Path::new(&format!("src/{}.rs", module))) || ctx.fs.exists(Path::new(&format!("src/{}/mod.rs", module)))
+    });
+
+    for (path, visibility) in [("src/main.rs", ""), ("src/lib.rs", "pub ")] {
+        if !ctx.fs.exists(Path::new(path)) {
+            continue;
+        }
+        let mut file = RustFile::read(&*ctx.fs, path)?;
+        let mut changed = false;
+        for module in &modules {
+            if !file.has_mod(module) {
+                file.add_mod(&format!("{}mod {};", visibility, module), None).map_err(|e| RvyError::edit(path, e))?;
+                changed = true;
             }
-        })
-        .collect()
+        }
+        if changed {
+            file.save(ctx)?;
+        }
+    }
+    Ok(())
 }
 
-pub fn write_file(ctx: &Context, path: &str, content: &str) {
-    let path = Path::new(path);
+pub fn write_file(ctx: &Context, path: &str, content: &str) -> Result<(), RvyError> {
+    write_generated(ctx, path, content, None)
+}
 
-    if path.exists() && !ctx.force {
-        println!(
-            "Skip {} (already exists, use --force to overwrite)",
-            path.display()
-        );
-        return;
-    }
+/// Render `template` for entity `name` and write it to `path`.
+pub fn write_rendered(ctx: &Context, path: &str, template: &str, name: &str) -> Result<(), RvyError> {
+    let content = render(ctx, template, name)?;
+    write_generated(ctx, path, &content, Some(template))
+}
 
-    if ctx.dry_run {
-        println!("[DRY RUN] Would write {}", path.display());
-        return;
-    }
+/// Write `content` rendered from `template`. Existing files are only replaced
+/// with --force, and only if they are still what rvy generated; hand-edited
+/// or unknown files also need --force-modified.
+pub fn write_generated(ctx: &Context, path_str: &str, content: &str, template: Option<&str>) -> Result<(), RvyError> {
+    let path = Path::new(path_str);
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).unwrap();
+    let exists = ctx.fs.exists(path);
+    if exists {
+        if ctx.merge {
+            return merge_generated(ctx, path_str, content, template);
+        }
+        if !ctx.force {
+            if file_state(ctx, path_str)? == FileState::Modified {
+                ctx.report.skipped(path_str, "modified since it was generated");
+            } else {
+                ctx.report.skipped(path_str, "already exists, use --force to overwrite");
+            }
+            return Ok(());
+        }
+        if !overwrite_allowed(ctx, path_str)? {
+            return Ok(());
+        }
     }
 
-    fs::write(path, content).unwrap();
-    println!("Created {}", path.display());
+    write_recorded(ctx, path_str, content, content, template)?;
+    if exists {
+        ctx.report.modified(path_str, None);
+    } else {
+        ctx.report.created(path_str);
+    }
+    Ok(())
 }
 
-pub fn update_module_exports(ctx: &Context, mod_path: &str, module_name: &str) {
-    if ctx.dry_run {
-        return;
+/// Three-way merge the new output into an existing file, keeping the edits
+/// made since it was generated.
+pub fn merge_generated(ctx: &Context, path: &str, content: &str, template: Option<&str>) -> Result<(), RvyError> {
+    let current = ctx.fs.read(Path::new(path)).map_err(|e| RvyError::io(path, e))?;
+    let state = file_state(ctx, path)?;
+    let base = manifest::base(&*ctx.fs, path)?;
+
+    let merged = match base {
+        Some(base) => merge::merge3(&base, &current, content),
+        // Generated before base copies were kept: nothing to merge against
+        None if state == FileState::Unmodified => merge::Merged { content: content.to_string(), conflicts: 0 },
+        None => {
+            let reason = match state {
+                FileState::Modified => "modified, and no base copy to merge against",
+                _ => "not generated by rvy",
+            };
+            ctx.report.skipped(path, reason);
+            return Ok(());
+        }
+    };
+
+    if merged.content == current {
+        manifest::record(&*ctx.fs, path, content, template)?;
+        ctx.report.skipped(path, "unchanged");
+        return Ok(());
     }
-    
-    let path = Path::new(mod_path);
-    let current_content = fs::read_to_string(path).unwrap_or_else(|_| String::new());
-    
-    let export_line = format!("pub mod {};", module_name);
-    if current_content.contains(&export_line) {
-        return;
+
+    // The new output is the base for the next merge
+    write_recorded(ctx, path, &merged.content, content, template)?;
+    ctx.report.modified(path, Some("merged"));
+    if merged.conflicts > 0 {
+        let message = format!("{} merge conflict(s), resolve the <<<<<<< markers", merged.conflicts);
+        ctx.report.warning(Some(path), &message);
     }
-    
-    let new_content = if current_content.contains("// Add your modules here") {
-        current_content.replace(
-            "// Add your modules here",
-            &format!("pub mod {};\n// Add your modules here", module_name)
-        )
+    Ok(())
+}
+
+// Write `content` to `path` and record `generated` as what rvy generated there
+fn write_recorded(ctx: &Context, path: &str, content: &str, generated: &str, template: Option<&str>) -> Result<(), RvyError> {
+    ctx.fs.write(Path::new(path), content).map_err(|e| RvyError::io(path, e))?;
+    manifest::record(&*ctx.fs, path, generated, template)
+}
+
+/// Whether --force may replace the existing file `path`: only when it is
+/// exactly what rvy generated, unless --force-modified is given.
+pub fn overwrite_allowed(ctx: &Context, path: &str) -> Result<bool, RvyError> {
+    let reason = match file_state(ctx, path)? {
+        FileState::Unmodified => return Ok(true),
+        _ if ctx.force_modified => return Ok(true),
+        FileState::Modified => "modified since it was generated",
+        FileState::Untracked => "not generated by rvy",
+    };
+    ctx.report.skipped(path, &format!("{}, use --force-modified to overwrite", reason));
+    Ok(false)
+}
+
+/// Manifest state of the existing file `path`.
+pub fn file_state(ctx: &Context, path: &str) -> Result<FileState, RvyError> {
+    let current = ctx.fs.read(Path::new(path)).map_err(|e| RvyError::io(path, e))?;
+    manifest::state(&*ctx.fs, path, &current)
+}
+
+pub fn update_module_exports(ctx: &Context, mod_path: &str, module_name: &str) -> Result<(), RvyError> {
+    let current_content = if ctx.fs.exists(Path::new(mod_path)) {
+        ctx.fs.read(Path::new(mod_path)).map_err(|e| RvyError::io(mod_path, e))?
     } else {
-        format!("{}\npub mod {};\n", current_content.trim(), module_name)
+        String::new()
     };
     
-    fs::write(path, new_content).unwrap();
+    let mut file = RustFile::parse(mod_path, current_content).map_err(|e| RvyError::edit(mod_path, e))?;
+    if file.has_mod(module_name) {
+        return Ok(());
+    }
+    file.add_mod(&format!("pub mod {};", module_name), Some(&ctx.config.mod_marker))
+        .map_err(|e| RvyError::edit(mod_path, e))?;
+    file.save(ctx)
+}
+
+/// Remove `mod module_name` from `mod_path`. Returns whether it was declared.
+pub fn remove_module_export(ctx: &Context, mod_path: &str, module_name: &str) -> Result<bool, RvyError> {
+    if !ctx.fs.exists(Path::new(mod_path)) {
+        return Ok(false);
+    }
+    
+    let mut file = RustFile::read(&*ctx.fs, mod_path)?;
+    if !file.remove_mod(module_name).map_err(|e| RvyError::edit(mod_path, e))? {
+        return Ok(false);
+    }
+    file.save(ctx)?;
+    Ok(true)
 }
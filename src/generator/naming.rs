@@ -0,0 +1,240 @@
+// Identifier casing and English pluralization.
+//
+// Every generator derives file names, type names, table names and routes from
+// an entity name through this module, so `UserProfile`, `user-profile` and
+// `userProfile` all produce the same code.
+
+use crate::generator::field::RUST_KEYWORDS;
+
+// Singular -> plural for words that don't follow the suffix rules
+const IRREGULAR: &[(&str, &str)] = &[
+    ("person", "people"),
+    ("man", "men"),
+    ("woman", "women"),
+    ("child", "children"),
+    ("tooth", "teeth"),
+    ("foot", "feet"),
+    ("mouse", "mice"),
+    ("goose", "geese"),
+    ("ox", "oxen"),
+    ("leaf", "leaves"),
+    ("knife", "knives"),
+    ("life", "lives"),
+    ("wife", "wives"),
+    ("half", "halves"),
+    ("wolf", "wolves"),
+    ("shelf", "shelves"),
+    ("thief", "thieves"),
+    ("loaf", "loaves"),
+    ("calf", "calves"),
+    ("hero", "heroes"),
+    ("potato", "potatoes"),
+    ("tomato", "tomatoes"),
+    ("echo", "echoes"),
+    ("cactus", "cacti"),
+    ("radius", "radii"),
+    ("criterion", "criteria"),
+    ("phenomenon", "phenomena"),
+    ("quiz", "quizzes"),
+];
+
+// Words whose plural is the same as the singular
+const UNCOUNTABLE: &[&str] = &[
+    "sheep", "fish", "deer", "series", "species", "news", "data", "metadata",
+    "information", "equipment", "feedback", "media", "software", "staff",
+];
+
+/// Naming forms of one entity, with optional per-entity overrides for the
+/// plural and the table name.
+#[derive(Debug, Clone)]
+pub struct Naming {
+    pub snake: String,
+    pub pascal: String,
+    pub camel: String,
+    pub kebab: String,
+    pub screaming: String,
+    pub plural: String,
+    pub table: String,
+}
+
+impl Naming {
+    pub fn new(name: &str, plural: Option<&str>, table: Option<&str>) -> Self {
+        let snake = to_snake_case(name);
+        let plural = plural.map(to_snake_case).unwrap_or_else(|| pluralize(&snake));
+        let table = table.map(str::to_string).unwrap_or_else(|| plural.clone());
+
+        Self {
+            pascal: to_pascal_case(&snake),
+            camel: to_camel_case(&snake),
+            kebab: to_kebab_case(&snake),
+            screaming: to_screaming_case(&snake),
+            snake,
+            plural,
+            table,
+        }
+    }
+
+    pub fn plural_pascal(&self) -> String {
+        to_pascal_case(&self.plural)
+    }
+
+    /// URL path segment for the entity's collection, e.g. `user-profiles`.
+    pub fn route(&self) -> String {
+        to_kebab_case(&self.plural)
+    }
+}
+
+// Split any casing into lowercase words: `HTTPServer2Config`, `http_server2-config`
+// and `httpServer2Config` all give ["http", "server2", "config"]
+fn words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            // fooBar -> foo|Bar, HTTPServer -> HTTP|Server
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+pub fn to_snake_case(s: &str) -> String {
+    words(s).join("_")
+}
+
+pub fn to_kebab_case(s: &str) -> String {
+    words(s).join("-")
+}
+
+pub fn to_screaming_case(s: &str) -> String {
+    to_snake_case(s).to_uppercase()
+}
+
+pub fn to_pascal_case(s: &str) -> String {
+    words(s).iter().map(|w| capitalize(w)).collect()
+}
+
+pub fn to_camel_case(s: &str) -> String {
+    let pascal = to_pascal_case(s);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_lowercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+/// `user_name` -> `User name`
+pub fn humanize(s: &str) -> String {
+    capitalize(&words(s).join(" "))
+}
+
+/// Pluralize the last word of an identifier, keeping its casing style:
+/// `category` -> `categories`, `user_person` -> `user_people`,
+/// `BusStop` -> `BusStops`.
+pub fn pluralize(s: &str) -> String {
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+    let start = s
+        .rfind(|c: char| !c.is_alphanumeric())
+        .map(|i| i + 1)
+        .or_else(|| {
+            chars
+                .windows(2)
+                .filter(|w| w[1].1.is_uppercase() && !w[0].1.is_uppercase())
+                .map(|w| w[1].0)
+                .next_back()
+        })
+        .unwrap_or(0);
+    let (head, last) = s.split_at(start);
+    format!("{}{}", head, pluralize_word(last))
+}
+
+fn pluralize_word(word: &str) -> String {
+    let lower = word.to_lowercase();
+    if lower.is_empty() || UNCOUNTABLE.contains(&lower.as_str()) {
+        return word.to_string();
+    }
+
+    if let Some((_, plural)) = IRREGULAR.iter().find(|(singular, _)| lower == *singular) {
+        // Keep the first letter's case (Person -> People)
+        return if word.starts_with(char::is_uppercase) {
+            capitalize(plural)
+        } else {
+            plural.to_string()
+        };
+    }
+
+    let stem = |n: usize| &word[..word.len() - n];
+    if lower.ends_with("is") {
+        // analysis -> analyses
+        return format!("{}es", stem(2));
+    }
+    if ["s", "x", "z", "ch", "sh"].iter().any(|end| lower.ends_with(end)) {
+        return format!("{}es", word);
+    }
+    let mut rev = lower.chars().rev();
+    if rev.next() == Some('y') && rev.next().is_some_and(|c| !"aeiou".contains(c)) {
+        return format!("{}ies", stem(1));
+    }
+    format!("{}s", word)
+}
+
+/// The entity name as a valid Rust identifier in snake_case, or why it isn't one.
+pub fn entity_name(name: &str) -> Result<String, String> {
+    let snake = to_snake_case(name);
+    if snake.is_empty() {
+        return Err(format!("'{}' is not a valid entity name", name));
+    }
+    if snake.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(format!("entity name '{}' must not start with a digit", name));
+    }
+    if !snake.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("entity name '{}' must be ASCII", name));
+    }
+    if RUST_KEYWORDS.contains(&snake.as_str()) {
+        return Err(format!("entity name '{}' is a Rust keyword", name));
+    }
+    Ok(snake)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_keywords_as_entity_names() {
+        for keyword in ["box", "try", "yield", "macro", "gen", "Virtual"] {
+            assert!(entity_name(keyword).is_err(), "{} was accepted", keyword);
+        }
+        assert_eq!(entity_name("Macro Item").unwrap(), "macro_item");
+    }
+
+    #[test]
+    fn rejects_underscore_as_entity_name() {
+        assert!(entity_name("_").is_err());
+    }
+}
@@ -1,41 +1,64 @@
+use crate::config::{ProjectKind, CONFIG_FILE, DEFAULT_MOD_MARKER};
 use crate::context::Context;
-use crate::generator::{render, write_file};
-use std::fs;
+use crate::error::RvyError;
+use crate::generator::cargo::{Workspace, MANIFEST};
+use crate::generator::{render, write_file, write_generated, write_rendered};
+use std::path::Path;
 
-pub fn generate(ctx: &Context, name: &str) {
+pub fn generate(ctx: &Context, name: &str) -> Result<(), RvyError> {
     if ctx.dry_run {
-        println!("[DRY RUN] Would create project {}", name);
-        return;
+        ctx.report.info(&format!("[DRY RUN] Would create project {}", name));
+    } else {
+        ctx.report.info(&format!("Creating project: {}", name));
     }
 
-    // Create project directory
-    fs::create_dir_all(name).unwrap();
-    println!("Creating project: {}", name);
-
-    // Generate Cargo.toml
-    let cargo_template = include_str!("../../templates/project/Cargo.toml.tpl");
-    let cargo_content = render(cargo_template, name);
-    let cargo_path = format!("{}/Cargo.toml", name);
-    write_file(ctx, &cargo_path, &cargo_content);
+    // Generate Cargo.toml, as a member of the workspace around it if any
+    let cargo_path = format!("{}/{}", name, MANIFEST);
+    let template = "project/Cargo.toml.tpl";
+    let mut manifest = render(ctx, template, name)?;
+    let workspace = Workspace::around(ctx, name)?;
+    if let Some(workspace) = &workspace {
+        manifest = workspace.inherit(&manifest)?;
+    }
+    write_generated(ctx, &cargo_path, &manifest, Some(template))?;
+    if let Some(mut workspace) = workspace {
+        add_to_workspace(ctx, &mut workspace)?;
+    }
 
-    // Generate main.rs
-    let main_template = include_str!("../../templates/project/main.rs.tpl");
-    let main_content = render(main_template, name);
-    let main_path = format!("{}/src/main.rs", name);
-    write_file(ctx, &main_path, &main_content);
+    // Generate main.rs; a library has none
+    if ctx.config.kind != ProjectKind::Lib {
+        let main_path = format!("{}/src/main.rs", name);
+        write_rendered(ctx, &main_path, "project/main.rs.tpl", name)?;
+    }
 
     // Generate lib.rs
-    let lib_template = include_str!("../../templates/project/lib.rs.tpl");
-    let lib_content = render(lib_template, name);
     let lib_path = format!("{}/src/lib.rs", name);
-    write_file(ctx, &lib_path, &lib_content);
+    write_rendered(ctx, &lib_path, "project/lib.rs.tpl", name)?;
+
+    // Generate rvy.toml with the default layout
+    let config_path = format!("{}/{}", name, CONFIG_FILE);
+    write_rendered(ctx, &config_path, "project/rvy.toml.tpl", name)?;
 
-    // Create empty module directories
-    let dirs = ["service", "usecase", "repository", "data", "adapter", "config", "factory", "handler"];
-    for dir in &dirs {
-        let mod_path = format!("{}/src/{}/mod.rs", name, dir);
-        write_file(ctx, &mod_path, "// Add your modules here\n");
+    // Create empty module directories for the layers of this kind of project
+    for layer in ctx.config.kind.layers() {
+        let mod_path = format!("{}/{}", name, ctx.config.mod_file(layer));
+        write_file(ctx, &mod_path, &format!("{}\n", DEFAULT_MOD_MARKER))?;
     }
 
-    println!("✓ Project '{}' created successfully!", name);
+    if !ctx.dry_run {
+        ctx.report.info(&format!("✓ Project '{}' created successfully!", name));
+    }
+    Ok(())
+}
+
+fn add_to_workspace(ctx: &Context, workspace: &mut Workspace) -> Result<(), RvyError> {
+    if workspace.includes_member() {
+        ctx.report.info(&format!("Workspace {} already includes {}", workspace.manifest_path, workspace.member));
+        return Ok(());
+    }
+    let updated = workspace.add_member();
+    let path = workspace.manifest_path.clone();
+    ctx.fs.write(Path::new(&path), &updated).map_err(|e| RvyError::io(&path, e))?;
+    ctx.report.modified(&path, Some(&format!("added workspace member {}", workspace.member)));
+    Ok(())
 }
@@ -1,13 +1,15 @@
+use crate::config::Layer;
 use crate::context::Context;
-use crate::generator::{render, write_file, update_module_exports};
+use crate::error::RvyError;
+use crate::generator::cargo::{self, dep};
+use crate::generator::{write_rendered, update_module_exports};
 
-pub fn generate(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/repository.rs.tpl");
+pub fn generate(ctx: &Context, name: &str) -> Result<(), RvyError> {
 
-    let content = render(template, name);
+    let config = &ctx.config;
+    let path = config.file_path(Layer::Repository, name);
 
-    let path = format!("src/repository/{}.rs", name);
-
-    write_file(ctx, &path, &content);
-    update_module_exports(ctx, "src/repository/mod.rs", name);
+    cargo::require(ctx, &[dep("async-trait", "0.1")])?;
+    write_rendered(ctx, &path, "repository.rs.tpl", name)?;
+    update_module_exports(ctx, &config.mod_file(Layer::Repository), &config.module_name(Layer::Repository, name))
 }
@@ -0,0 +1,517 @@
+// Structural edits of Rust source files (main.rs, lib.rs, mod.rs).
+//
+// The file is parsed with syn only to find where an item or expression is;
+// new code is spliced in at those byte offsets, so the rest of the file keeps
+// its formatting and comments exactly as written.
+
+use std::ops::Range;
+use std::path::Path;
+
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::{Expr, ExprMethodCall, Item, ItemFn, Local, Pat, Stmt, UseTree};
+
+use crate::context::Context;
+use crate::error::RvyError;
+use crate::generator::manifest;
+use crate::vfs::FileSystem;
+
+pub struct RustFile {
+    path: String,
+    original: String,
+    source: String,
+    ast: syn::File,
+}
+
+impl RustFile {
+    pub fn read(fs: &dyn FileSystem, path: &str) -> Result<Self, RvyError> {
+        let source = fs.read(Path::new(path)).map_err(|e| RvyError::io(path, e))?;
+        Self::parse(path, source).map_err(|e| RvyError::edit(path, e))
+    }
+
+    pub fn parse(path: &str, source: String) -> Result<Self, String> {
+        let ast = parse(path, &source)?;
+        Ok(Self { path: path.to_string(), original: source.clone(), source, ast })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Save the edits. A generated file stays counted as unmodified in the
+    /// manifest, since the edit is rvy's own.
+    pub fn save(&self, ctx: &Context) -> Result<(), RvyError> {
+        let fs = &*ctx.fs;
+        let exists = fs.exists(Path::new(&self.path));
+        fs.write(Path::new(&self.path), &self.source).map_err(|e| RvyError::io(&self.path, e))?;
+        manifest::refresh(fs, &self.path, &self.original, &self.source)?;
+        if exists {
+            ctx.report.modified(&self.path, None);
+        } else {
+            ctx.report.created(&self.path);
+        }
+        Ok(())
+    }
+
+    fn splice(&mut self, range: Range<usize>, text: &str) -> Result<(), String> {
+        let mut source = self.source.clone();
+        source.replace_range(range, text);
+        self.ast = parse(&self.path, &source).map_err(|e| format!("edit produced invalid code: {}", e))?;
+        self.source = source;
+        Ok(())
+    }
+
+    fn insert(&mut self, at: usize, text: &str) -> Result<(), String> {
+        self.splice(at..at, text)
+    }
+
+    /// Whether the file declares `mod name` (with any visibility).
+    pub fn has_mod(&self, name: &str) -> bool {
+        self.ast.items.iter().any(|item| matches!(item, Item::Mod(m) if m.ident == name))
+    }
+
+    /// Add a module declaration such as `pub mod user_service;`. It goes on
+    /// the line before `marker` when the file has one, otherwise after the
+    /// last module declaration.
+    pub fn add_mod(&mut self, decl: &str, marker: Option<&str>) -> Result<(), String> {
+        if let Some(pos) = marker.and_then(|marker| self.source.find(marker)) {
+            let line_start = self.source[..pos].rfind('\n').map_or(0, |i| i + 1);
+            if self.source[line_start..pos].trim().is_empty() {
+                return self.insert(line_start, &format!("{}{}\n", &self.source[line_start..pos], decl));
+            }
+        }
+
+        let last_mod = self.ast.items.iter().rev().find_map(|item| match item {
+            Item::Mod(m) => Some(match (&m.semi, &m.content) {
+                (Some(semi), _) => end(semi.span),
+                (None, Some((brace, _))) => end(brace.span.close()),
+                (None, None) => end(m.span()),
+            }),
+            _ => None,
+        });
+        match last_mod {
+            Some(at) => self.insert(at, &format!("\n{}", decl)),
+            None if self.ast.items.is_empty() => self.prepend_item(decl),
+            // A blank line between the new declaration and the code below it
+            None => self.prepend_item(&format!("{}\n", decl)),
+        }
+    }
+
+    /// Whether a `use` item imports `path` or something under it.
+    pub fn has_use(&self, path: &str) -> bool {
+        let nested = format!("{}::", path);
+        self.ast.items.iter().any(|item| match item {
+            Item::Use(u) => use_paths(&u.tree, String::new())
+                .iter()
+                .any(|p| p == path || p.starts_with(&nested)),
+            _ => false,
+        })
+    }
+
+    /// Add a `use` item after the last one, or after the module declarations
+    /// when the file has no imports yet.
+    pub fn add_use(&mut self, item: &str) -> Result<(), String> {
+        let last = |uses: bool| {
+            self.ast.items.iter().rev().find_map(|i| match i {
+                Item::Use(u) if uses => Some(end(u.semi_token.span)),
+                Item::Mod(m) if !uses => m.semi.map(|semi| end(semi.span)),
+                _ => None,
+            })
+        };
+        if let Some(at) = last(true) {
+            self.insert(at, &format!("\n{}{}", item, self.blank_line_at(at)))
+        } else if let Some(at) = last(false) {
+            self.insert(at, &format!("\n\n{}{}", item, self.blank_line_at(at)))
+        } else {
+            self.prepend_item(&format!("{}\n", item))
+        }
+    }
+
+    // "\n" when the code after `at` starts on the very next line, to keep it
+    // apart from the items inserted there
+    fn blank_line_at(&self, at: usize) -> &'static str {
+        let rest = &self.source[at..];
+        match rest.strip_prefix('\n') {
+            Some(next) if !next.starts_with('\n') && !next.trim().is_empty() => "\n",
+            _ => "",
+        }
+    }
+
+    fn prepend_item(&mut self, text: &str) -> Result<(), String> {
+        match self.ast.items.first() {
+            Some(item) => {
+                let at = start(item.span());
+                self.insert(at, &format!("{}\n", text))
+            }
+            None => {
+                let separator = if self.source.trim().is_empty() { "" } else { "\n" };
+                let at = self.source.len();
+                self.insert(at, &format!("{}{}\n", separator, text))
+            }
+        }
+    }
+
+    pub fn has_fn(&self, name: &str) -> bool {
+        self.function(name).is_ok()
+    }
+
+    fn function(&self, name: &str) -> Result<&ItemFn, String> {
+        self.ast
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Fn(f) if f.sig.ident == name => Some(f),
+                _ => None,
+            })
+            .ok_or_else(|| format!("no `fn {}` found", name))
+    }
+
+    /// Replace the whole function `name`, attributes included.
+    pub fn replace_fn(&mut self, name: &str, text: &str) -> Result<(), String> {
+        let f = self.function(name)?;
+        let from = f.attrs.first().map_or_else(|| start(f.span()), |attr| start(attr.pound_token.span));
+        let to = end(f.block.brace_token.span.close());
+        self.splice(from..to, text.trim_end())
+    }
+
+    fn locals(&self, function: &str) -> Result<Vec<(String, &Local)>, String> {
+        Ok(self
+            .function(function)?
+            .block
+            .stmts
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Local(local) => Some((binding(&local.pat)?, local)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Whether `function` binds `var` with a top-level `let`.
+    pub fn has_local(&self, function: &str, var: &str) -> Result<bool, String> {
+        Ok(self.locals(function)?.iter().any(|(name, _)| name == var))
+    }
+
+    /// Whether `function` has any `let` statement at all.
+    pub fn has_locals(&self, function: &str) -> Result<bool, String> {
+        Ok(!self.locals(function)?.is_empty())
+    }
+
+    /// Insert `stmts` into `function` after the last `let` whose binding
+    /// starts with `after_prefix`, or else right before `let <before>`.
+    pub fn insert_stmts(&mut self, function: &str, after_prefix: &str, before: &str, stmts: &[String]) -> Result<(), String> {
+        let locals = self.locals(function)?;
+        let (at, text) = match locals.iter().rev().find(|(name, _)| name.starts_with(after_prefix)) {
+            Some((_, local)) => {
+                let indent = self.indent(start(local.let_token.span)).unwrap_or("    ");
+                (end(local.semi_token.span), stmts.iter().map(|s| format!("\n{}{}", indent, s)).collect::<String>())
+            }
+            None => {
+                let (_, local) = locals
+                    .iter()
+                    .find(|(name, _)| name == before)
+                    .ok_or_else(|| format!("`fn {}` has no `let {}` binding", function, before))?;
+                let at = start(local.let_token.span);
+                let indent = self.indent(at).unwrap_or("    ");
+                (at, stmts.iter().map(|s| format!("{}\n{}", s, indent)).collect::<String>())
+            }
+        };
+        self.insert(at, &text)
+    }
+
+    // The method-call chain initializing `let <var> = ...;` in `function`,
+    // outermost call first, plus the expression the chain starts from. A
+    // closure initializer (actix's `let app = move || App::new()...;`)
+    // stands for the chain it returns.
+    fn chain(&self, function: &str, var: &str) -> Result<(Vec<&ExprMethodCall>, &Expr), String> {
+        let locals = self.locals(function)?;
+        let (_, local) = locals
+            .into_iter()
+            .find(|(name, _)| name == var)
+            .ok_or_else(|| format!("`fn {}` has no `let {}` binding", function, var))?;
+        let init = local
+            .init
+            .as_ref()
+            .ok_or_else(|| format!("`let {}` in `fn {}` has no initializer", var, function))?;
+        Ok(method_chain(closure_result(&init.expr)))
+    }
+
+    /// Append `.method(arg)` to the chain bound to `var`, right after the
+    /// last call to `method` whose argument satisfies `matches`, or after
+    /// the chain's root when there is none.
+    pub fn add_chain_call(
+        &mut self,
+        function: &str,
+        var: &str,
+        method: &str,
+        arg: &str,
+        matches: impl Fn(&Expr) -> bool,
+    ) -> Result<(), String> {
+        let (calls, root) = self.chain(function, var)?;
+        let anchor = calls
+            .iter()
+            .find(|call| call.method == method && call.args.first().is_some_and(&matches));
+        let at = match anchor {
+            Some(call) => end(call.paren_token.span.close()),
+            None => expr_end(root),
+        };
+        // Continue a multi-line chain on its own line
+        let layout = calls.first().and_then(|call| self.indent(start(call.dot_token.span)));
+        let text = match layout {
+            Some(indent) => format!("\n{}.{}({})", indent, method, arg),
+            None => format!(".{}({})", method, arg),
+        };
+        self.insert(at, &text)
+    }
+
+    /// Append `.method(args)` to the argument of the call in the chain bound
+    /// to `var` whose argument satisfies `matches`. Returns false when no
+    /// such call exists.
+    pub fn extend_chain_arg(
+        &mut self,
+        function: &str,
+        var: &str,
+        matches: impl Fn(&Expr) -> bool,
+        call: &str,
+    ) -> Result<bool, String> {
+        let (calls, _) = self.chain(function, var)?;
+        let Some(arg) = calls.iter().find_map(|c| c.args.first().filter(|arg| matches(arg))) else {
+            return Ok(false);
+        };
+        let at = expr_end(arg);
+        // Keep one call per line if the argument is already laid out that way
+        let (arg_calls, _) = method_chain(arg);
+        let text = match arg_calls.first().and_then(|c| self.indent(start(c.dot_token.span))) {
+            Some(indent) => format!("\n{}{}", indent, call),
+            None => call.to_string(),
+        };
+        self.insert(at, &text)?;
+        Ok(true)
+    }
+
+    /// Append an item at the end of the file, after a blank line.
+    pub fn append_item(&mut self, text: &str) -> Result<(), String> {
+        let body = self.source.trim_end().len();
+        let separator = if body == 0 { "" } else { "\n\n" };
+        self.splice(body..self.source.len(), &format!("{}{}\n", separator, text.trim_end()))
+    }
+
+    /// Replace the inline module `mod name` with `text`, keeping its place.
+    pub fn replace_mod(&mut self, name: &str, text: &str) -> Result<(), String> {
+        let range = self.ast.items.iter().find_map(|item| match item {
+            Item::Mod(m) if m.ident == name => Some(item_range(item)),
+            _ => None,
+        });
+        let range = range.ok_or_else(|| format!("no `mod {}` found", name))?;
+        self.splice(range, text.trim_end())
+    }
+
+    /// Remove the declaration (or inline module) `mod name`.
+    pub fn remove_mod(&mut self, name: &str) -> Result<bool, String> {
+        let range = self.ast.items.iter().find_map(|item| match item {
+            Item::Mod(m) if m.ident == name => Some(item_range(item)),
+            _ => None,
+        });
+        self.remove(range)
+    }
+
+    /// Remove the `use` items that only import `path` or things under it.
+    pub fn remove_use(&mut self, path: &str) -> Result<bool, String> {
+        let nested = format!("{}::", path);
+        let mut removed = false;
+        loop {
+            let range = self.ast.items.iter().find_map(|item| match item {
+                Item::Use(u) => use_paths(&u.tree, String::new())
+                    .iter()
+                    .all(|p| p == path || p.starts_with(&nested))
+                    .then(|| item_range(item)),
+                _ => None,
+            });
+            if !self.remove(range)? {
+                return Ok(removed);
+            }
+            removed = true;
+        }
+    }
+
+    /// Names of the methods called in the chain bound to `var`, outermost first.
+    pub fn chain_methods(&self, function: &str, var: &str) -> Result<Vec<String>, String> {
+        let (calls, _) = self.chain(function, var)?;
+        Ok(calls.iter().map(|call| call.method.to_string()).collect())
+    }
+
+    /// Whether the chain bound to `var` has a `.method(..)` call matching `matches`.
+    pub fn has_chain_call(&self, function: &str, var: &str, matches: impl Fn(&ExprMethodCall) -> bool) -> Result<bool, String> {
+        let (calls, _) = self.chain(function, var)?;
+        Ok(calls.into_iter().any(matches))
+    }
+
+    /// Remove the first top-level statement of `function` that is not a
+    /// `let` and whose source contains `needle`.
+    pub fn remove_stmt_containing(&mut self, function: &str, needle: &str) -> Result<bool, String> {
+        let range = self.function(function)?.block.stmts.iter().find_map(|stmt| {
+            let range = match stmt {
+                Stmt::Expr(..) | Stmt::Macro(..) => stmt.span().byte_range(),
+                _ => return None,
+            };
+            self.source[range.clone()].contains(needle).then_some(range)
+        });
+        self.remove(range)
+    }
+
+    /// Remove the top-level `let var` statement of `function`.
+    pub fn remove_local(&mut self, function: &str, var: &str) -> Result<bool, String> {
+        let range = self
+            .locals(function)?
+            .into_iter()
+            .find(|(name, _)| name == var)
+            .map(|(_, local)| start(local.let_token.span)..end(local.semi_token.span));
+        self.remove(range)
+    }
+
+    /// Remove the first `.method(..)` call matching `matches` from the chain
+    /// bound to `var`, including chains nested in the calls' arguments.
+    pub fn remove_chain_call(
+        &mut self,
+        function: &str,
+        var: &str,
+        matches: impl Fn(&ExprMethodCall) -> bool,
+    ) -> Result<bool, String> {
+        let (calls, _) = self.chain(function, var)?;
+        let mut pending = calls;
+        let mut range = None;
+        while let Some(call) = pending.pop() {
+            if matches(call) {
+                range = Some(start(call.dot_token.span)..end(call.paren_token.span.close()));
+                break;
+            }
+            for arg in &call.args {
+                pending.extend(method_chain(arg).0);
+            }
+        }
+        self.remove(range)
+    }
+
+    // Remove `range`, along with its line when nothing else is on it, or the
+    // line break before it when it starts a line (a chained `.call()`)
+    fn remove(&mut self, range: Option<Range<usize>>) -> Result<bool, String> {
+        let Some(range) = range else {
+            return Ok(false);
+        };
+        let line_start = self.source[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[range.end..].find('\n').map_or(self.source.len(), |i| range.end + i);
+        let alone_before = self.source[line_start..range.start].trim().is_empty();
+        let alone_after = self.source[range.end..line_end].trim().is_empty();
+        let range = match (alone_before, alone_after) {
+            (true, true) => line_start..(line_end + 1).min(self.source.len()),
+            (true, false) => line_start.saturating_sub(1)..range.end,
+            (false, _) => range,
+        };
+        self.splice(range, "")?;
+        Ok(true)
+    }
+
+    // Leading whitespace of the line containing `at`, if nothing else
+    // precedes `at` on that line
+    fn indent(&self, at: usize) -> Option<&str> {
+        let line_start = self.source[..at].rfind('\n').map_or(0, |i| i + 1);
+        let indent = &self.source[line_start..at];
+        indent.trim().is_empty().then_some(indent)
+    }
+}
+
+fn parse(path: &str, source: &str) -> Result<syn::File, String> {
+    syn::parse_str(source).map_err(|e| {
+        let at = e.span().start();
+        format!("cannot parse {}:{}:{}: {}", path, at.line, at.column + 1, e)
+    })
+}
+
+fn start(span: Span) -> usize {
+    span.byte_range().start
+}
+
+fn end(span: Span) -> usize {
+    span.byte_range().end
+}
+
+fn expr_end(expr: &Expr) -> usize {
+    match expr {
+        Expr::MethodCall(call) => end(call.paren_token.span.close()),
+        Expr::Call(call) => end(call.paren_token.span.close()),
+        other => end(other.span()),
+    }
+}
+
+// An item's full extent, attributes and doc comments included
+fn item_range(item: &Item) -> Range<usize> {
+    let span = item.span();
+    let attrs = match item {
+        Item::Mod(m) => &m.attrs,
+        Item::Use(u) => &u.attrs,
+        _ => return span.byte_range(),
+    };
+    let from = attrs.first().map_or(start(span), |attr| start(attr.pound_token.span));
+    from..end(span)
+}
+
+fn binding(pat: &Pat) -> Option<String> {
+    match pat {
+        Pat::Ident(ident) => Some(ident.ident.to_string()),
+        Pat::Type(typed) => binding(&typed.pat),
+        _ => None,
+    }
+}
+
+fn use_paths(tree: &UseTree, prefix: String) -> Vec<String> {
+    let join = |name: String| if prefix.is_empty() { name } else { format!("{}::{}", prefix, name) };
+    match tree {
+        UseTree::Path(p) => use_paths(&p.tree, join(p.ident.to_string())),
+        UseTree::Name(n) => vec![join(n.ident.to_string())],
+        UseTree::Rename(r) => vec![join(r.ident.to_string())],
+        UseTree::Glob(_) => vec![join("*".to_string())],
+        UseTree::Group(g) => g.items.iter().flat_map(|t| use_paths(t, prefix.clone())).collect(),
+    }
+}
+
+// `|| { a.b() }` -> `a.b()`; any other expression is returned as is
+fn closure_result(expr: &Expr) -> &Expr {
+    let Expr::Closure(closure) = expr else {
+        return expr;
+    };
+    match closure.body.as_ref() {
+        Expr::Block(block) => match block.block.stmts.last() {
+            Some(Stmt::Expr(tail, None)) => tail,
+            _ => expr,
+        },
+        body => body,
+    }
+}
+
+/// `a.b().c()` -> ([c, b], a)
+pub fn method_chain(expr: &Expr) -> (Vec<&ExprMethodCall>, &Expr) {
+    let mut calls = Vec::new();
+    let mut expr = expr;
+    while let Expr::MethodCall(call) = expr {
+        calls.push(call);
+        expr = &call.receiver;
+    }
+    (calls, expr)
+}
+
+/// Whether `expr` is the variable `var`, possibly with calls on it (`var.clone()`).
+pub fn uses_var(expr: &Expr, var: &str) -> bool {
+    matches!(method_chain(expr).1, Expr::Path(path) if path.path.is_ident(var))
+}
+
+/// Whether `expr` calls a path ending in `segments`, e.g. `["SwaggerUi", "new"]`.
+pub fn calls_path(expr: &Expr, segments: &[&str]) -> bool {
+    let Expr::Call(call) = expr else {
+        return false;
+    };
+    let Expr::Path(path) = call.func.as_ref() else {
+        return false;
+    };
+    let names: Vec<String> = path.path.segments.iter().map(|s| s.ident.to_string()).collect();
+    names.len() >= segments.len() && names[names.len() - segments.len()..].iter().zip(segments).all(|(a, b)| a == b)
+}
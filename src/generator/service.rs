@@ -1,15 +1,15 @@
+use crate::config::Layer;
 use crate::context::Context;
-use crate::generator::{render, write_file, update_module_exports};
+use crate::error::RvyError;
+use crate::generator::{write_rendered, update_module_exports};
 
-pub fn generate(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/service.rs.tpl");
+pub fn generate(ctx: &Context, name: &str) -> Result<(), RvyError> {
 
-    let content = render(template, name);
+    let config = &ctx.config;
+    let path = config.file_path(Layer::Service, name);
 
-    let path = format!("src/service/{}_service.rs", name);
-
-    write_file(ctx, &path, &content);
+    write_rendered(ctx, &path, "service.rs.tpl", name)?;
     
     // Update mod.rs
-    update_module_exports(ctx, "src/service/mod.rs", &format!("{}_service", name));
+    update_module_exports(ctx, &config.mod_file(Layer::Service), &config.module_name(Layer::Service, name))
 }
@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::PathBuf;
+
+use minijinja::value::Value;
+use minijinja::{Environment, ErrorKind, UndefinedBehavior};
+
+use crate::context::Context;
+use crate::error::RvyError;
+use crate::generator::naming::{
+    pluralize, to_camel_case, to_kebab_case, to_pascal_case, to_screaming_case, to_snake_case,
+};
+use crate::generator::write_file;
+
+// Overrides are looked up here (relative to the project root) before the user
+// directory and the embedded templates
+pub const PROJECT_TEMPLATE_DIR: &str = ".rvy/templates";
+
+// Every template shipped with rvy, keyed by its path under `templates/`.
+// Templates can include or import each other through these paths.
+pub const EMBEDDED: &[(&str, &str)] = &[
+    ("service.rs.tpl", include_str!("../../templates/service.rs.tpl")),
+    ("usecase.rs.tpl", include_str!("../../templates/usecase.rs.tpl")),
+    ("repository.rs.tpl", include_str!("../../templates/repository.rs.tpl")),
+    ("data.rs.tpl", include_str!("../../templates/data.rs.tpl")),
+    ("entity.rs.tpl", include_str!("../../templates/entity.rs.tpl")),
+    ("handler.rs.tpl", include_str!("../../templates/handler.rs.tpl")),
+    ("auth.rs.tpl", include_str!("../../templates/auth.rs.tpl")),
+    ("handler_api_doc.rs.tpl", include_str!("../../templates/handler_api_doc.rs.tpl")),
+    ("error.rs.tpl", include_str!("../../templates/error.rs.tpl")),
+    ("adapter/postgres.rs.tpl", include_str!("../../templates/adapter/postgres.rs.tpl")),
+    ("adapter/mysql.rs.tpl", include_str!("../../templates/adapter/mysql.rs.tpl")),
+    ("adapter/mongodb.rs.tpl", include_str!("../../templates/adapter/mongodb.rs.tpl")),
+    ("adapter/sqlite.rs.tpl", include_str!("../../templates/adapter/sqlite.rs.tpl")),
+    ("config/database.rs.tpl", include_str!("../../templates/config/database.rs.tpl")),
+    ("factory/repository_factory.rs.tpl", include_str!("../../templates/factory/repository_factory.rs.tpl")),
+    ("examples/main_with_crud.rs.tpl", include_str!("../../templates/examples/main_with_crud.rs.tpl")),
+    ("examples/USAGE.md.tpl", include_str!("../../templates/examples/USAGE.md.tpl")),
+    ("migrations/postgres_create_table.sql.tpl", include_str!("../../templates/migrations/postgres_create_table.sql.tpl")),
+    ("migrations/mysql_create_table.sql.tpl", include_str!("../../templates/migrations/mysql_create_table.sql.tpl")),
+    ("migrations/sqlite_create_table.sql.tpl", include_str!("../../templates/migrations/sqlite_create_table.sql.tpl")),
+    ("migrations/mongodb_setup.rs.tpl", include_str!("../../templates/migrations/mongodb_setup.rs.tpl")),
+    ("main/server.rs.tpl", include_str!("../../templates/main/server.rs.tpl")),
+    ("main/stub.rs.tpl", include_str!("../../templates/main/stub.rs.tpl")),
+    ("actix/handler.rs.tpl", include_str!("../../templates/actix/handler.rs.tpl")),
+    ("actix/main/server.rs.tpl", include_str!("../../templates/actix/main/server.rs.tpl")),
+    ("actix/main/stub.rs.tpl", include_str!("../../templates/actix/main/stub.rs.tpl")),
+    ("actix/tests/integration_test.rs.tpl", include_str!("../../templates/actix/tests/integration_test.rs.tpl")),
+    ("grpc/entity.proto.tpl", include_str!("../../templates/grpc/entity.proto.tpl")),
+    ("grpc/build.rs.tpl", include_str!("../../templates/grpc/build.rs.tpl")),
+    ("grpc/server.rs.tpl", include_str!("../../templates/grpc/server.rs.tpl")),
+    ("grpc/status.rs.tpl", include_str!("../../templates/grpc/status.rs.tpl")),
+    ("grpc/test.rs.tpl", include_str!("../../templates/grpc/test.rs.tpl")),
+    ("graphql/entity.rs.tpl", include_str!("../../templates/graphql/entity.rs.tpl")),
+    ("graphql/schema.rs.tpl", include_str!("../../templates/graphql/schema.rs.tpl")),
+    ("graphql/test.rs.tpl", include_str!("../../templates/graphql/test.rs.tpl")),
+    ("project/Cargo.toml.tpl", include_str!("../../templates/project/Cargo.toml.tpl")),
+    ("project/main.rs.tpl", include_str!("../../templates/project/main.rs.tpl")),
+    ("project/lib.rs.tpl", include_str!("../../templates/project/lib.rs.tpl")),
+    ("project/rvy.toml.tpl", include_str!("../../templates/project/rvy.toml.tpl")),
+    ("tests/common.rs.tpl", include_str!("../../templates/tests/common.rs.tpl")),
+    ("tests/common_entity.rs.tpl", include_str!("../../templates/tests/common_entity.rs.tpl")),
+    ("tests/service_test.rs.tpl", include_str!("../../templates/tests/service_test.rs.tpl")),
+    ("tests/auth_test.rs.tpl", include_str!("../../templates/tests/auth_test.rs.tpl")),
+    ("tests/integration_test.rs.tpl", include_str!("../../templates/tests/integration_test.rs.tpl")),
+    ("partials/test_data.rs.tpl", include_str!("../../templates/partials/test_data.rs.tpl")),
+    ("partials/permissions.rs.tpl", include_str!("../../templates/partials/permissions.rs.tpl")),
+];
+
+pub fn embedded(path: &str) -> Option<&'static str> {
+    EMBEDDED
+        .iter()
+        .find(|(name, _)| *name == path)
+        .map(|(_, source)| *source)
+}
+
+/// `$XDG_CONFIG_HOME/rvy/templates`, falling back to `~/.config/rvy/templates`.
+pub fn user_template_dir() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("rvy").join("templates"))
+}
+
+fn override_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(PROJECT_TEMPLATE_DIR)];
+    dirs.extend(user_template_dir());
+    dirs
+}
+
+// A file with the same relative path in an override directory shadows the
+// embedded template
+fn load(path: &str) -> Result<Option<String>, minijinja::Error> {
+    for dir in override_dirs() {
+        let file = dir.join(path);
+        if file.is_file() {
+            return fs::read_to_string(&file).map(Some).map_err(|e| {
+                minijinja::Error::new(
+                    ErrorKind::InvalidOperation,
+                    format!("cannot read template override {}", file.display()),
+                )
+                .with_source(e)
+            });
+        }
+    }
+    Ok(embedded(path).map(str::to_string))
+}
+
+/// Source of the template `path` as it will be rendered, override or built-in.
+/// An override that can't be read is an error, as it is for `render`.
+pub fn source(path: &str) -> Result<Option<String>, RvyError> {
+    load(path).map_err(|e| RvyError::Template(format!("{:#}", e)))
+}
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+
+    // Referencing a variable that wasn't provided is an error, not empty output
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    // Block tags on their own line don't leave blank lines behind
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+
+    env.set_loader(load);
+
+    env.add_filter("snake", |s: String| to_snake_case(&s));
+    env.add_filter("pascal", |s: String| to_pascal_case(&s));
+    env.add_filter("camel", |s: String| to_camel_case(&s));
+    env.add_filter("kebab", |s: String| to_kebab_case(&s));
+    env.add_filter("screaming", |s: String| to_screaming_case(&s));
+    env.add_filter("plural", |s: String| pluralize(&s));
+    // `upper`, `lower`, `indent`, `join`, ... are minijinja builtins
+
+    env
+}
+
+/// Render the template at `path` (relative to `templates/`) with `vars`.
+pub fn render(path: &str, vars: Value) -> Result<String, minijinja::Error> {
+    let env = environment();
+    let template = env.get_template(path)?;
+    template.render(vars)
+}
+
+/// Copy embedded templates into `.rvy/templates/` (or the user directory) so
+/// they can be edited. `path` selects a single template or a directory such
+/// as `adapter`; everything is ejected when it's omitted.
+pub fn eject(ctx: &Context, path: Option<&str>, user: bool) -> Result<(), RvyError> {
+    let target = if user {
+        user_template_dir().ok_or_else(|| {
+            RvyError::Usage("Cannot locate the user template directory (HOME is not set)".to_string())
+        })?
+    } else {
+        PathBuf::from(PROJECT_TEMPLATE_DIR)
+    };
+
+    let prefix = path.map(|p| p.trim_matches('/'));
+    let selected: Vec<_> = EMBEDDED
+        .iter()
+        .filter(|(name, _)| match prefix {
+            None | Some("") => true,
+            Some(p) => *name == p || name.starts_with(&format!("{}/", p)),
+        })
+        .collect();
+
+    if selected.is_empty() {
+        let mut message = format!("No built-in template matches '{}'\nAvailable templates:", path.unwrap_or_default());
+        for (name, _) in EMBEDDED {
+            message.push_str(&format!("\n  {}", name));
+        }
+        return Err(RvyError::Usage(message));
+    }
+
+    for (name, source) in selected {
+        let file = target.join(name);
+        write_file(ctx, &file.to_string_lossy(), source)?;
+    }
+    Ok(())
+}
@@ -1,32 +1,30 @@
+use crate::config::{Layer, WebFramework};
 use crate::context::Context;
-use crate::generator::render;
-use std::fs;
+use crate::error::RvyError;
+use crate::generator::cargo::{self, crate_name, dep};
+use crate::generator::{manifest, merge_generated, render, write_rendered};
+use crate::generator::rust_file::RustFile;
 use std::path::Path;
 
-pub fn generate_unit_tests(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/tests/service_test.rs.tpl");
-    let content = render(template, name);
+pub fn generate_unit_tests(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    cargo::require(ctx, &[dep("tokio", "1").features(&["full"]).dev(), dep("async-trait", "0.1")])?;
+    let content = render(ctx, "tests/service_test.rs.tpl", name)?;
     
     // Append tests to service file
-    let service_path = format!("src/service/{}_service.rs", name);
+    let service_path = ctx.config.file_path(Layer::Service, name);
     
-    if ctx.dry_run {
-        println!("[DRY RUN] Would add unit tests to {}", service_path);
-        return;
+    if !ctx.fs.exists(Path::new(&service_path)) {
+        return Err(RvyError::Usage(format!("{} does not exist. Generate service first.", service_path)));
     }
     
-    if !Path::new(&service_path).exists() {
-        eprintln!("Error: {} does not exist. Generate service first.", service_path);
-        return;
-    }
-    
-    let mut current_content = fs::read_to_string(&service_path).unwrap();
+    let original = ctx.fs.read(Path::new(&service_path)).map_err(|e| RvyError::io(&service_path, e))?;
+    let mut current_content = original.clone();
     
     // Check if tests already exist
     if current_content.contains("#[cfg(test)]") {
         if !ctx.force {
-            println!("Skip adding tests to {} (already exists, use --force to overwrite)", service_path);
-            return;
+            ctx.report.skipped(&service_path, "already has tests, use --force to overwrite");
+            return Ok(());
         }
         // Remove existing test module
         if let Some(pos) = current_content.find("#[cfg(test)]") {
@@ -38,79 +36,103 @@ pub fn generate_unit_tests(ctx: &Context, name: &str) {
     current_content.push_str("\n\n");
     current_content.push_str(&content);
     
-    fs::write(&service_path, current_content).unwrap();
-    println!("Added unit tests to {}", service_path);
+    ctx.fs.write(Path::new(&service_path), &current_content).map_err(|e| RvyError::io(&service_path, e))?;
+    manifest::refresh(&*ctx.fs, &service_path, &original, &current_content)?;
+    ctx.report.modified(&service_path, Some("unit tests added"));
+    Ok(())
 }
 
-pub fn generate_integration_tests(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/tests/integration_test.rs.tpl");
-    let content = render(template, name);
-    
-    let test_path = format!("tests/{}_test.rs", name);
-    
-    if ctx.dry_run {
-        println!("[DRY RUN] Would create {}", test_path);
-        return;
-    }
-    
-    let path = Path::new(&test_path);
-    
-    if path.exists() && !ctx.force {
-        println!("Skip {} (already exists, use --force to overwrite)", test_path);
-        return;
+pub fn generate_integration_tests(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let web = ctx.config.web;
+    let deps = match web {
+        WebFramework::Axum => vec![
+            dep("tokio", "1").features(&["full"]),
+            cargo::web_crate(web),
+            dep("serde_json", "1.0"),
+            dep("async-trait", "0.1"),
+            dep("tower", "0.5").dev(),
+        ],
+        // Tests run on actix's own runtime through actix_web::test
+        WebFramework::Actix => vec![cargo::web_crate(web), dep("serde_json", "1.0"), dep("async-trait", "0.1")],
+    };
+    cargo::require(ctx, &deps)?;
+    let test_path = format!("{}/{}_test.rs", ctx.config.tests_dir, name);
+    write_rendered(ctx, &test_path, &web.template("tests/integration_test.rs.tpl"), name)?;
+    
+    // Also add the entity's mocks to the common test utilities
+    create_test_common(ctx, name)
+}
+
+pub fn create_test_common(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    let common_path = format!("{}/common.rs", ctx.config.tests_dir);
+    
+    let exists = ctx.fs.exists(Path::new(&common_path));
+    let content = if exists {
+        ctx.fs.read(Path::new(&common_path)).map_err(|e| RvyError::io(&common_path, e))?
+    } else {
+        render(ctx, "tests/common.rs.tpl", name)?
+    };
+    
+    let crate_name = crate_name(ctx).unwrap_or_else(|| name.to_string());
+    let module = ctx.naming(name).snake;
+    let entity = render(ctx, "tests/common_entity.rs.tpl", name)?
+        .replace("crate::", &format!("{}::", crate_name));
+    
+    // Each entity's mocks live in their own `pub mod <entity>`
+    let edit = |e| RvyError::edit(&common_path, e);
+    let mut common = RustFile::parse(&common_path, content).map_err(edit)?;
+    if common.has_mod(&module) {
+        if ctx.merge {
+            merge_test_common(ctx, &common_path, &module, &entity)?;
+        }
+        return Ok(());
     }
     
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).unwrap();
+    common.append_item(&entity).map_err(edit)?;
+    common.save(ctx)?;
+    if !exists {
+        manifest::record(&*ctx.fs, &common_path, common.source(), Some("tests/common.rs.tpl"))?;
     }
-    
-    fs::write(path, content).unwrap();
-    println!("Created {}", test_path);
-    
-    // Also create common test utilities if they don't exist
-    create_test_common(ctx, name);
+    Ok(())
 }
 
-fn create_test_common(_ctx: &Context, name: &str) {
-    let common_path = "tests/common.rs";
-    
-    if Path::new(common_path).exists() {
-        return; // Already exists
+// Regenerate only this entity's module in the base copy and merge that,
+// so the other entities' modules and any edits are left alone
+fn merge_test_common(ctx: &Context, common_path: &str, module: &str, entity: &str) -> Result<(), RvyError> {
+    let Some(base) = manifest::base(&*ctx.fs, common_path)? else {
+        ctx.report.skipped(common_path, "no base copy to merge against");
+        return Ok(());
+    };
+    let edit = |e| RvyError::edit(common_path, e);
+    let mut generated = RustFile::parse(common_path, base).map_err(edit)?;
+    if generated.has_mod(module) {
+        generated.replace_mod(module, entity).map_err(edit)?;
+    } else {
+        generated.append_item(entity).map_err(edit)?;
     }
-    
-    // Read package name from Cargo.toml
-    let package_name = get_package_name().unwrap_or_else(|| name.to_string());
-    
-    let template = include_str!("../../templates/tests/common.rs.tpl");
-    let mut content = render(template, name);
-    
-    // Replace placeholders with actual crate name (snake_case version)
-    let crate_name = package_name.replace('-', "_");
-    content = content.replace("crate::", &format!("{}::", crate_name));
-    
-    fs::write(common_path, content).unwrap();
-    println!("Created {}", common_path);
+    merge_generated(ctx, common_path, generated.source(), Some("tests/common.rs.tpl"))
 }
 
-fn get_package_name() -> Option<String> {
-    let cargo_path = "Cargo.toml";
-    if !Path::new(cargo_path).exists() {
-        return None;
+/// Remove the entity's module from the shared test utilities.
+pub fn remove_test_common(ctx: &Context, name: &str) -> Result<bool, RvyError> {
+    let common_path = format!("{}/common.rs", ctx.config.tests_dir);
+    if !ctx.fs.exists(Path::new(&common_path)) {
+        return Ok(false);
     }
     
-    let content = fs::read_to_string(cargo_path).ok()?;
-    for line in content.lines() {
-        if line.trim().starts_with("name") {
-            if let Some(name) = line.split('=').nth(1) {
-                let name = name.trim().trim_matches('"').trim();
-                return Some(name.to_string());
-            }
-        }
+    let mut common = RustFile::read(&*ctx.fs, &common_path)?;
+    if !common.remove_mod(&ctx.naming(name).snake).map_err(|e| RvyError::edit(&common_path, e))? {
+        return Ok(false);
     }
-    None
+    common.save(ctx)?;
+    Ok(true)
 }
 
-pub fn generate_all_tests(ctx: &Context, name: &str) {
-    generate_unit_tests(ctx, name);
-    generate_integration_tests(ctx, name);
+pub fn generate_all_tests(ctx: &Context, name: &str) -> Result<(), RvyError> {
+    generate_unit_tests(ctx, name)?;
+    // Integration tests go through the HTTP router
+    if ctx.config.kind.http() {
+        generate_integration_tests(ctx, name)?;
+    }
+    Ok(())
 }
@@ -1,13 +1,13 @@
+use crate::config::Layer;
 use crate::context::Context;
-use crate::generator::{render, write_file, update_module_exports};
+use crate::error::RvyError;
+use crate::generator::{write_rendered, update_module_exports};
 
-pub fn generate(ctx: &Context, name: &str) {
-    let template = include_str!("../../templates/usecase.rs.tpl");
+pub fn generate(ctx: &Context, name: &str) -> Result<(), RvyError> {
 
-    let content = render(template, name);
+    let config = &ctx.config;
+    let path = config.file_path(Layer::Usecase, name);
 
-    let path = format!("src/usecase/{}_usecase.rs", name);
-
-    write_file(ctx, &path, &content);
-    update_module_exports(ctx, "src/usecase/mod.rs", &format!("{}_usecase", name));
+    write_rendered(ctx, &path, "usecase.rs.tpl", name)?;
+    update_module_exports(ctx, &config.mod_file(Layer::Usecase), &config.module_name(Layer::Usecase, name))
 }
@@ -1,10 +1,28 @@
+use clap::builder::PossibleValuesParser;
 use clap::{Parser, Subcommand};
 
+mod config;
 mod context;
+mod error;
 mod generator;
+mod report;
+mod schema;
+mod transaction;
+mod vfs;
 
+use config::{Layer, ProjectConfig, ProjectKind, WebFramework};
 use context::Context;
-use generator::dispatch::{dispatch, generate_all, GenKind};
+use error::RvyError;
+use generator::field::{self, Field};
+use generator::naming;
+use generator::dispatch::{dispatch, generate_all, generate_entities, GenKind};
+use generator::cargo::{self, MANIFEST};
+use generator::DATABASES;
+use report::{Output, Reporter};
+use schema::{parse_databases, Permissions, Schema, SCHEMA_FILE};
+use std::path::PathBuf;
+use std::rc::Rc;
+use transaction::Transaction;
 
 #[derive(Parser)]
 #[command(name = "rvy")]
@@ -17,9 +35,25 @@ struct Cli {
     #[arg(long, global = true)]
     dry_run: bool,
 
-    /// Overwrite existing files
+    /// Overwrite existing files that are unchanged since rvy generated them
     #[arg(long, global = true)]
     force: bool,
+
+    /// Overwrite existing files, even ones edited since rvy generated them
+    #[arg(long, global = true)]
+    force_modified: bool,
+
+    /// With --dry-run, print a unified diff of every file that would change
+    #[arg(long, global = true, requires = "dry_run")]
+    diff: bool,
+
+    /// Print a human-readable log, or a JSON report of every event once done
+    #[arg(long, global = true, value_enum, default_value_t = Output::Text)]
+    output: Output,
+
+    /// Run as if rvy was started in DIR (default: the nearest directory with a Cargo.toml)
+    #[arg(short = 'C', long, global = true, value_name = "DIR")]
+    project_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -33,7 +67,41 @@ enum Commands {
     /// Generate all layers (service, usecase, repository, data, handler, adapters)
     #[command(name = "gen-all")]
     GenAll {
-        /// Name of the component
+        /// Name of the component (all schema entities when omitted with --from)
+        #[arg(required_unless_present = "from", value_parser = naming::entity_name)]
+        name: Option<String>,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
+
+        /// Read entity definitions from a schema file (e.g. rvy.entities.toml)
+        #[arg(long, value_name = "FILE", conflicts_with = "fields")]
+        from: Option<PathBuf>,
+    },
+
+    /// Generate every entity defined in the schema file
+    Sync {
+        /// Schema file [default: rvy.entities.toml]
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+
+    /// Regenerate an entity's files, merging in the edits made since
+    Regen {
+        /// Entity name
+        #[arg(value_parser = naming::entity_name)]
+        name: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
+    },
+
+    /// Remove everything gen-all generated for an entity
+    Destroy {
+        /// Entity name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
     },
 
@@ -42,6 +110,12 @@ enum Commands {
         #[command(subcommand)]
         command: GenCommands,
     },
+
+    /// Manage template overrides
+    Templates {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -50,6 +124,31 @@ enum NewCommands {
     Project {
         /// Project name
         name: String,
+
+        /// Databases to support, comma-separated (default: all)
+        #[arg(long = "db", value_name = "DB", value_delimiter = ',', value_parser = PossibleValuesParser::new(DATABASES))]
+        databases: Vec<String>,
+
+        /// Kind of project to create
+        #[arg(long, value_enum, default_value_t = ProjectKind::Api)]
+        template: ProjectKind,
+
+        /// HTTP framework of an api project (default: axum)
+        #[arg(long, value_enum)]
+        web: Option<WebFramework>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplateCommands {
+    /// Copy built-in templates to .rvy/templates/ for customization
+    Eject {
+        /// Template or directory to eject, e.g. adapter/postgres.rs.tpl or adapter (all when omitted)
+        path: Option<String>,
+
+        /// Eject into the user template directory (~/.config/rvy/templates) instead
+        #[arg(long)]
+        user: bool,
     },
 }
 
@@ -58,159 +157,450 @@ enum GenCommands {
     /// Generate a service
     Service {
         /// Service name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
     },
 
     /// Generate a usecase
     Usecase {
         /// Usecase name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
     },
 
     /// Generate a repository
     Repository {
         /// Repository name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
     },
 
     /// Generate a data model
     Data {
         /// Data model name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
     },
 
     /// Generate API handler
     Handler {
         /// Handler name
+        #[arg(value_parser = naming::entity_name)]
+        name: String,
+    },
+
+    /// Generate a gRPC server (tonic) for an entity's service
+    Grpc {
+        /// Entity name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
+    },
+
+    /// Generate GraphQL queries and mutations (async-graphql) for an entity's service
+    Graphql {
+        /// Entity name
+        #[arg(value_parser = naming::entity_name)]
+        name: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
     },
 
     /// Regenerate Swagger/OpenAPI documentation
     Swagger {
         /// Component name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
     },
 
     /// Generate database adapter(s)
     Adapter {
         /// Component name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
 
         /// Database type: postgres, mysql, mongodb, sqlite, or 'all' for all types
         #[arg(short, long, default_value = "all")]
         db_type: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
     },
 
     /// Generate database config
     Config {
         /// Component name (for template variable replacement)
+        #[arg(value_parser = naming::entity_name)]
         name: String,
     },
 
     /// Generate repository factory
     Factory {
         /// Component name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
     },
 
     /// Generate usage examples and documentation
     Example {
         /// Component name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
     },
 
     /// Generate unit tests for service layer
     Test {
         /// Component name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
     },
 
     /// Generate integration tests
     #[command(name = "integration-test")]
     IntegrationTest {
         /// Component name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
     },
 
     /// Generate database migration(s)
     Migration {
         /// Component name
+        #[arg(value_parser = naming::entity_name)]
         name: String,
 
         /// Database type: postgres, mysql, sqlite, mongodb, or 'all' for all types
         #[arg(short, long, default_value = "all")]
         db_type: String,
+
+        /// Entity field as NAME:TYPE, append `?` for nullable (repeatable)
+        #[arg(long = "field", value_name = "NAME:TYPE", value_parser = Field::parse)]
+        fields: Vec<Field>,
     },
 }
 
 fn main() {
     let cli = Cli::parse();
+    let report = Rc::new(Reporter::new(cli.output, cli.dry_run));
+
+    let result = run(cli, report.clone());
+    report.finish(&result);
+    if let Err(e) = result {
+        std::process::exit(e.exit_code());
+    }
+}
+
+fn run(mut cli: Cli, report: Rc<Reporter>) -> Result<(), RvyError> {
+    if cli.diff && cli.output == Output::Json {
+        return Err(RvyError::Usage("--diff prints a text diff and cannot be combined with --output json".to_string()));
+    }
+
+    if let Some(dir) = &cli.project_dir {
+        std::env::set_current_dir(dir)
+            .map_err(|e| RvyError::Usage(format!("Cannot use project directory {}: {}", dir.display(), e)))?;
+    }
+    // A new project is created right here, not in the project around it
+    if !matches!(cli.command, Commands::New { .. }) {
+        enter_project(&mut cli.command, &report)?;
+    }
+
+    let config = ProjectConfig::load_default().map_err(RvyError::Config)?;
+
+    // Files are written out only once the whole command has succeeded
+    let transaction = Transaction::begin();
 
     let mut ctx = Context {
         dry_run: cli.dry_run,
-        force: cli.force,
+        force: cli.force || cli.force_modified,
+        force_modified: cli.force_modified,
+        merge: false,
         is_new_all: false,
+        fields: Vec::new(),
+        databases: config.databases.clone(),
+        plural: None,
+        table: None,
+        permissions: Permissions::default(),
+        config,
+        fs: transaction.fs(),
+        report: report.clone(),
     };
 
     match cli.command {
         Commands::New { command } => match command {
-            NewCommands::Project { name } => {
-                generator::project::generate(&ctx, &name);
+            NewCommands::Project { name, databases, template, web } => {
+                // The new project starts from the default layout, not the current directory's rvy.toml
+                let mut config = ProjectConfig::default();
+                config.kind = template;
+                if !databases.is_empty() && !template.has(Layer::Adapter) {
+                    return Err(RvyError::Usage(format!("{} projects have no database code; drop --db", template.name())));
+                }
+                if let Some(web) = web {
+                    if !template.http() {
+                        return Err(RvyError::Usage(format!("{} projects have no HTTP server; drop --web", template.name())));
+                    }
+                    config.web = web;
+                }
+                if !databases.is_empty() {
+                    config.databases = parse_databases(&databases).map_err(RvyError::Usage)?;
+                }
+                ctx.databases = config.databases.clone();
+                ctx.config = config;
+                report.within("project", || generator::project::generate(&ctx, &name))?;
             }
         },
 
-        Commands::GenAll { name } => {
+        Commands::GenAll { name, fields: _, from: Some(file) } => {
+            let schema = Schema::load(&file).map_err(RvyError::Config)?;
+            let entities = match name {
+                Some(name) => match schema.entity(&name, &ctx.config.databases).map_err(RvyError::Config)? {
+                    Some(entity) => vec![entity],
+                    None => {
+                        return Err(RvyError::Usage(format!("Entity '{}' is not defined in {}", name, file.display())));
+                    }
+                },
+                None => schema.resolve(&ctx.config.databases).map_err(RvyError::Config)?,
+            };
+            generate_entities(&ctx, &entities)?;
+        }
+
+        Commands::GenAll { name, fields, from: None } => {
+            let name = name.expect("name is required without --from");
             ctx.is_new_all = true;  // Set flag for gen-all
-            generate_all(&ctx, &name);
+            apply_entity(&mut ctx, &name, fields)?;
+            generate_all(&ctx, &name)?;
+        }
+
+        Commands::Sync { file } => {
+            let file = file.unwrap_or_else(|| PathBuf::from(SCHEMA_FILE));
+            let schema = Schema::load(&file).map_err(RvyError::Config)?;
+            let entities = schema.resolve(&ctx.config.databases).map_err(RvyError::Config)?;
+            generate_entities(&ctx, &entities)?;
+        }
+
+        Commands::Regen { name, fields } => {
+            ctx.is_new_all = true;
+            ctx.merge = true;
+            apply_entity(&mut ctx, &name, fields)?;
+            generate_all(&ctx, &name)?;
+        }
+
+        Commands::Destroy { name } => {
+            apply_entity(&mut ctx, &name, Vec::new())?;
+            report.within("destroy", || generator::destroy::destroy(&ctx, &name))?;
         }
 
         Commands::Gen { command } => match command {
-            GenCommands::Service { name } => dispatch(GenKind::Service, &ctx, &name),
+            GenCommands::Service { name } => {
+                apply_entity(&mut ctx, &name, Vec::new())?;
+                dispatch(GenKind::Service, &ctx, &name)?
+            }
+
+            GenCommands::Usecase { name, fields } => {
+                apply_entity(&mut ctx, &name, fields)?;
+                dispatch(GenKind::Usecase, &ctx, &name)?
+            }
 
-            GenCommands::Usecase { name } => dispatch(GenKind::Usecase, &ctx, &name),
+            GenCommands::Repository { name } => {
+                apply_entity(&mut ctx, &name, Vec::new())?;
+                dispatch(GenKind::Repository, &ctx, &name)?
+            }
+
+            GenCommands::Data { name, fields } => {
+                apply_entity(&mut ctx, &name, fields)?;
+                dispatch(GenKind::Data, &ctx, &name)?
+            }
 
-            GenCommands::Repository { name } => dispatch(GenKind::Repository, &ctx, &name),
+            GenCommands::Handler { name } => {
+                apply_entity(&mut ctx, &name, Vec::new())?;
+                dispatch(GenKind::Handler, &ctx, &name)?
+            }
 
-            GenCommands::Data { name } => dispatch(GenKind::Data, &ctx, &name),
+            GenCommands::Grpc { name, fields } => {
+                apply_entity(&mut ctx, &name, fields)?;
+                dispatch(GenKind::Grpc, &ctx, &name)?
+            }
 
-            GenCommands::Handler { name } => dispatch(GenKind::Handler, &ctx, &name),
+            GenCommands::Graphql { name, fields } => {
+                apply_entity(&mut ctx, &name, fields)?;
+                dispatch(GenKind::Graphql, &ctx, &name)?
+            }
 
             GenCommands::Swagger { name } => {
-                // Force regenerate handler to update Swagger docs
-                let force_ctx = Context {
-                    dry_run: ctx.dry_run,
-                    force: true,  // Always force for swagger regeneration
-                    is_new_all: ctx.is_new_all,
+                apply_entity(&mut ctx, &name, Vec::new())?;
+                // Merge the regenerated handler so hand-written code survives
+                let merge_ctx = Context {
+                    merge: true,
+                    ..ctx.clone()
                 };
-                dispatch(GenKind::Handler, &force_ctx, &name);
+                dispatch(GenKind::Handler, &merge_ctx, &name)?;
             }
 
-            GenCommands::Adapter { name, db_type } => {
+            GenCommands::Adapter { name, db_type, fields } => {
+                apply_entity(&mut ctx, &name, fields)?;
                 if db_type.to_lowercase() == "all" {
-                    dispatch(GenKind::AdapterAll, &ctx, &name);
+                    dispatch(GenKind::AdapterAll, &ctx, &name)?;
                 } else {
-                    dispatch(GenKind::Adapter(db_type), &ctx, &name);
+                    require_enabled(&ctx, &db_type)?;
+                    dispatch(GenKind::Adapter(db_type), &ctx, &name)?;
                 }
             }
 
-            GenCommands::Config { name } => dispatch(GenKind::Config, &ctx, &name),
+            GenCommands::Config { name } => dispatch(GenKind::Config, &ctx, &name)?,
 
-            GenCommands::Factory { name } => dispatch(GenKind::Factory, &ctx, &name),
+            GenCommands::Factory { name } => {
+                apply_entity(&mut ctx, &name, Vec::new())?;
+                dispatch(GenKind::Factory, &ctx, &name)?
+            }
 
-            GenCommands::Example { name } => dispatch(GenKind::Example, &ctx, &name),
+            GenCommands::Example { name } => {
+                apply_entity(&mut ctx, &name, Vec::new())?;
+                dispatch(GenKind::Example, &ctx, &name)?
+            }
 
-            GenCommands::Test { name } => dispatch(GenKind::Test, &ctx, &name),
+            GenCommands::Test { name, fields } => {
+                apply_entity(&mut ctx, &name, fields)?;
+                dispatch(GenKind::Test, &ctx, &name)?
+            }
 
-            GenCommands::IntegrationTest { name } => dispatch(GenKind::IntegrationTest, &ctx, &name),
+            GenCommands::IntegrationTest { name, fields } => {
+                apply_entity(&mut ctx, &name, fields)?;
+                dispatch(GenKind::IntegrationTest, &ctx, &name)?
+            }
 
-            GenCommands::Migration { name, db_type } => {
+            GenCommands::Migration { name, db_type, fields } => {
+                apply_entity(&mut ctx, &name, fields)?;
                 if db_type.to_lowercase() == "all" {
-                    dispatch(GenKind::MigrationAll, &ctx, &name);
+                    dispatch(GenKind::MigrationAll, &ctx, &name)?;
                 } else {
-                    dispatch(GenKind::Migration(db_type), &ctx, &name);
+                    require_enabled(&ctx, &db_type)?;
+                    dispatch(GenKind::Migration(db_type), &ctx, &name)?;
                 }
             }
         },
+
+        Commands::Templates { command } => match command {
+            TemplateCommands::Eject { path, user } => {
+                report.within("templates", || generator::template::eject(&ctx, path.as_deref(), user))?;
+            }
+        },
+    }
+
+    if cli.diff {
+        generator::diff::print(&transaction.changes());
+    }
+    if !cli.dry_run {
+        // A failed write puts back the files already written
+        transaction.commit(&report).map_err(|e| e.in_step("writing files"))?;
+    }
+    Ok(())
+}
+
+
+// Generators work with paths relative to the crate root, so move to the
+// nearest directory with a Cargo.toml when started in a subdirectory. Schema
+// files given on the command line stay relative to where rvy was started.
+fn enter_project(command: &mut Commands, report: &Reporter) -> Result<(), RvyError> {
+    let cwd = std::env::current_dir().map_err(|e| RvyError::io(".", e))?;
+    let Some(root) = cwd.ancestors().find(|dir| dir.join(MANIFEST).is_file()) else {
+        return Ok(());
+    };
+    // Generated code belongs to one crate, not to the workspace around them
+    let manifest = std::fs::read_to_string(root.join(MANIFEST)).map_err(|e| RvyError::io(MANIFEST, e))?;
+    if let Some(members) = cargo::virtual_members(&manifest) {
+        return Err(RvyError::Usage(format!(
+            "{} is a workspace; run rvy in one of its crates or pass -C <crate> (members: {})",
+            root.display(),
+            members.join(", ")
+        )));
     }
+    if root == cwd {
+        return Ok(());
+    }
+
+    let file = match command {
+        Commands::GenAll { from, .. } => from.as_mut(),
+        Commands::Sync { file, .. } => file.as_mut(),
+        _ => None,
+    };
+    if let Some(file) = file {
+        *file = cwd.join(&*file);
+    }
+
+    std::env::set_current_dir(root).map_err(|e| RvyError::io(root.display(), e))?;
+    report.info(&format!("📁 Using project {}", root.display()));
+    Ok(())
+}
+
+// Fields given on the command line win; otherwise the entity's definition in
+// rvy.entities.toml is used when there is one, then the fields the entity was
+// last generated with.
+fn apply_entity(ctx: &mut Context, name: &str, fields: Vec<Field>) -> Result<(), RvyError> {
+    let schema = Schema::load_default().map_err(RvyError::Config)?;
+    let entity = schema
+        .map(|schema| schema.entity(name, &ctx.config.databases))
+        .transpose()
+        .map_err(RvyError::Config)?
+        .flatten();
+
+    // --field replaces the schema's fields, but the rest of its definition still applies
+    let has_entity = entity.is_some();
+    if let Some(entity) = entity {
+        ctx.fields = entity.fields;
+        ctx.databases = entity.databases;
+        ctx.plural = entity.plural;
+        ctx.table = entity.table;
+        ctx.permissions = entity.permissions;
+    }
+    if !fields.is_empty() {
+        ctx.fields = field::resolve(fields).map_err(|e| RvyError::Usage(format!("Invalid fields: {}", e)))?;
+    } else if !has_entity {
+        ctx.fields = match generator::manifest::entity_fields(&*ctx.fs, name)? {
+            Some(fields) => fields,
+            // Regenerating with made-up fields would rewrite the entity's code behind its migrations
+            None if ctx.merge => {
+                return Err(RvyError::Usage(format!(
+                    "No fields recorded for '{}'; pass them with --field or define the entity in {}",
+                    name, SCHEMA_FILE
+                )));
+            }
+            None => field::default_fields(),
+        };
+    }
+    Ok(())
 }
 
+// A known database that the project didn't select in rvy.toml would generate
+// code referencing a missing DatabaseType variant and sqlx feature
+fn require_enabled(ctx: &Context, db_type: &str) -> Result<(), RvyError> {
+    let db = db_type.to_lowercase();
+    if DATABASES.contains(&db.as_str()) && !ctx.config.databases.contains(&db) {
+        return Err(RvyError::Usage(format!(
+            "Database '{}' is not enabled for this project (rvy.toml databases: {})",
+            db,
+            ctx.config.databases.join(", ")
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// What a command did, for people or for tools wrapping rvy.
+//
+// Generators report every file they touch and every warning or tip through
+// the `Reporter` on `Context`. In text mode each event is printed as it
+// happens; with `--output json` they are collected and printed as a single
+// JSON document once the command has finished.
+
+use std::cell::RefCell;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::error::RvyError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Output {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Created,
+    Skipped,
+    Modified,
+    Deleted,
+    Warning,
+    Tip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// The generator that raised the event: `service`, `handler`, `migration`...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Reporter {
+    output: Output,
+    dry_run: bool,
+    events: RefCell<Vec<Event>>,
+    generators: RefCell<Vec<String>>,
+}
+
+impl Reporter {
+    pub fn new(output: Output, dry_run: bool) -> Self {
+        Self { output, dry_run, events: RefCell::default(), generators: RefCell::default() }
+    }
+
+    /// Run `f` with its events attributed to `generator`.
+    pub fn within<T>(&self, generator: &str, f: impl FnOnce() -> T) -> T {
+        self.generators.borrow_mut().push(generator.to_string());
+        let result = f();
+        self.generators.borrow_mut().pop();
+        result
+    }
+
+    pub fn created(&self, path: &str) {
+        self.file(Action::Created, path, None);
+    }
+
+    pub fn modified(&self, path: &str, detail: Option<&str>) {
+        self.file(Action::Modified, path, detail);
+    }
+
+    pub fn deleted(&self, path: &str) {
+        self.file(Action::Deleted, path, None);
+    }
+
+    pub fn skipped(&self, path: &str, reason: &str) {
+        self.file(Action::Skipped, path, Some(reason));
+    }
+
+    /// A warning already given in this command is not repeated.
+    pub fn warning(&self, path: Option<&str>, message: &str) {
+        let repeated = self.events.borrow().iter().any(|e| {
+            e.action == Action::Warning && e.path.as_deref() == path && e.message.as_deref() == Some(message)
+        });
+        if !repeated {
+            self.push(Action::Warning, path, Some(message));
+        }
+    }
+
+    pub fn tip(&self, message: &str) {
+        self.push(Action::Tip, None, Some(message));
+    }
+
+    /// Progress for people, e.g. which entity is being generated. Not an
+    /// event: JSON output leaves it out.
+    pub fn info(&self, message: &str) {
+        if self.output == Output::Text {
+            println!("{}", message);
+        }
+    }
+
+    fn file(&self, action: Action, path: &str, detail: Option<&str>) {
+        self.push(action, Some(path), detail);
+    }
+
+    fn push(&self, action: Action, path: Option<&str>, message: Option<&str>) {
+        let event = Event {
+            action,
+            path: path.map(str::to_string),
+            generator: self.generators.borrow().last().cloned(),
+            message: message.map(str::to_string),
+        };
+        if self.output == Output::Text {
+            println!("{}", self.line(&event));
+        }
+        self.events.borrow_mut().push(event);
+    }
+
+    fn line(&self, event: &Event) -> String {
+        let path = event.path.as_deref().unwrap_or_default();
+        let verb = match (event.action, self.dry_run) {
+            (Action::Created, false) => "Created",
+            (Action::Created, true) => "[DRY RUN] Would write",
+            (Action::Modified, false) => "Updated",
+            (Action::Modified, true) => "[DRY RUN] Would update",
+            (Action::Deleted, false) => "Removed",
+            (Action::Deleted, true) => "[DRY RUN] Would remove",
+            (Action::Skipped, _) => "Skip",
+            (Action::Warning, _) => "⚠️ ",
+            (Action::Tip, _) => "💡",
+        };
+        match (event.path.as_deref(), event.message.as_deref()) {
+            (Some(_), Some(message)) if event.action == Action::Warning => format!("{} {}: {}", verb, path, message),
+            (Some(_), Some(message)) => format!("{} {} ({})", verb, path, message),
+            (Some(_), None) => format!("{} {}", verb, path),
+            (None, message) => format!("{} {}", verb, message.unwrap_or_default()),
+        }
+    }
+
+    /// Print the outcome of the command: the error in text mode, or the
+    /// whole event list in JSON mode.
+    pub fn finish(&self, result: &Result<(), RvyError>) {
+        match self.output {
+            Output::Text => {
+                if let Err(e) = result {
+                    eprintln!("❌ {}", e);
+                    if !self.dry_run {
+                        eprintln!("   No files were changed");
+                    }
+                }
+            }
+            Output::Json => {
+                let error = result.as_ref().err().map(|e| {
+                    let (step, cause) = match e {
+                        RvyError::Step { step, source } => (Some(step), source.as_ref()),
+                        e => (None, e),
+                    };
+                    serde_json::json!({
+                        "code": e.exit_code(),
+                        "message": cause.to_string(),
+                        "step": step,
+                    })
+                });
+                let report = serde_json::json!({
+                    "dry_run": self.dry_run,
+                    "success": result.is_ok(),
+                    "events": *self.events.borrow(),
+                    "error": error,
+                });
+                println!("{}", serde_json::to_string_pretty(&report).expect("report serializes"));
+            }
+        }
+    }
+}
@@ -0,0 +1,242 @@
+// Project-level entity schema (`rvy.entities.toml`).
+//
+// [entities.product]
+// databases = ["postgres", "sqlite"]
+// plural = "products"        # optional, derived from the name by default
+// table = "catalog_products" # optional, defaults to the plural
+// fields = [
+//     { name = "title", type = "string", unique = true },
+//     { name = "price", type = "decimal", nullable = true, default = "0" },
+// ]
+//
+// # Roles or OAuth2 scopes a token needs for each operation, any one of them
+// # will do. Operations left out are open to any authenticated request.
+// [entities.product.permissions]
+// list = ["product:read", "admin"]
+// delete = ["admin"]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::generator::field::{self, Field};
+use crate::generator::naming;
+use crate::generator::DATABASES;
+
+pub const SCHEMA_FILE: &str = "rvy.entities.toml";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    #[serde(default)]
+    pub entities: BTreeMap<String, EntityDef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntityDef {
+    pub databases: Option<Vec<String>>,
+    pub plural: Option<String>,
+    pub table: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<FieldDef>,
+    #[serde(default)]
+    pub permissions: Permissions,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldDef {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub nullable: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unique: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+impl FieldDef {
+    pub fn from_field(field: &Field) -> Self {
+        Self {
+            name: field.name.clone(),
+            ty: field.ty.key().to_string(),
+            nullable: field.nullable,
+            unique: field.unique,
+            default: field.default.clone(),
+        }
+    }
+
+    pub fn to_field(&self) -> Result<Field, String> {
+        let mut field = Field::new(&self.name, &self.ty, self.nullable)?;
+        field.unique = self.unique;
+        field.default = self.default.clone();
+        Ok(field)
+    }
+}
+
+/// Roles or scopes required per CRUD operation; empty means any token.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Permissions {
+    #[serde(default)]
+    pub list: Vec<String>,
+    #[serde(default)]
+    pub get: Vec<String>,
+    #[serde(default)]
+    pub create: Vec<String>,
+    #[serde(default)]
+    pub update: Vec<String>,
+    #[serde(default)]
+    pub delete: Vec<String>,
+}
+
+impl Permissions {
+    /// Every role or scope named, once each
+    pub fn all(&self) -> Vec<String> {
+        let mut all: Vec<String> = Vec::new();
+        for permission in [&self.list, &self.get, &self.create, &self.update, &self.delete].into_iter().flatten() {
+            if !all.contains(permission) {
+                all.push(permission.clone());
+            }
+        }
+        all
+    }
+
+    // They end up in Rust string literals and space-separated scope claims
+    fn validate(&self) -> Result<(), String> {
+        let valid = |p: &str| {
+            !p.is_empty() && p.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '.' | '_' | '-' | '/' | '*'))
+        };
+        match self.all().into_iter().find(|p| !valid(p)) {
+            Some(p) => Err(format!("invalid permission '{}' (letters, digits and ':._-/*' only)", p)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// An entity with its fields and target databases validated.
+#[derive(Debug, Clone)]
+pub struct Entity {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub databases: Vec<String>,
+    pub plural: Option<String>,
+    pub table: Option<String>,
+    pub permissions: Permissions,
+}
+
+impl Schema {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        toml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))
+    }
+
+    /// Load the schema file from the current directory if there is one.
+    pub fn load_default() -> Result<Option<Self>, String> {
+        let path = Path::new(SCHEMA_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::load(path).map(Some)
+    }
+
+    /// Look up an entity; `UserProfile` and `user_profile` name the same one.
+    /// Entities without `databases` target `default_databases`.
+    pub fn entity(&self, name: &str, default_databases: &[String]) -> Result<Option<Entity>, String> {
+        let name = naming::to_snake_case(name);
+        self.entities
+            .iter()
+            .find(|(key, _)| naming::to_snake_case(key) == name)
+            .map(|(key, def)| def.resolve(key, default_databases))
+            .transpose()
+    }
+
+    pub fn resolve(&self, default_databases: &[String]) -> Result<Vec<Entity>, String> {
+        self.entities
+            .iter()
+            .map(|(name, def)| def.resolve(name, default_databases))
+            .collect()
+    }
+}
+
+impl EntityDef {
+    fn resolve(&self, key: &str, default_databases: &[String]) -> Result<Entity, String> {
+        let name = naming::entity_name(key)?;
+        let fields = self
+            .fields
+            .iter()
+            .map(FieldDef::to_field)
+            .collect::<Result<Vec<_>, String>>()
+            .and_then(field::resolve)
+            .map_err(|e| format!("entity '{}': {}", name, e))?;
+
+        let databases = match &self.databases {
+            Some(databases) => parse_databases(databases)
+                .map_err(|e| format!("entity '{}': {}", name, e))?,
+            None => default_databases.to_vec(),
+        };
+        if let Some(db) = databases.iter().find(|db| !default_databases.contains(db)) {
+            return Err(format!(
+                "entity '{}': database '{}' is not enabled in rvy.toml (databases: {})",
+                name,
+                db,
+                default_databases.join(", ")
+            ));
+        }
+
+        let plural = self
+            .plural
+            .as_deref()
+            .map(|plural| {
+                naming::entity_name(plural).map_err(|_| format!("entity '{}': invalid plural '{}'", name, plural))
+            })
+            .transpose()?;
+
+        let table = self
+            .table
+            .as_deref()
+            .map(|table| parse_table(table).map_err(|e| format!("entity '{}': {}", name, e)))
+            .transpose()?;
+
+        self.permissions.validate().map_err(|e| format!("entity '{}': {}", name, e))?;
+
+        Ok(Entity { name, fields, databases, plural, table, permissions: self.permissions.clone() })
+    }
+}
+
+pub fn parse_databases(databases: &[String]) -> Result<Vec<String>, String> {
+    if databases.is_empty() {
+        return Err("at least one database is required".to_string());
+    }
+
+    let mut parsed: Vec<String> = Vec::new();
+    for db in databases {
+        let db = db.to_lowercase();
+        if !DATABASES.contains(&db.as_str()) {
+            return Err(format!(
+                "unsupported database '{}' (supported: {})",
+                db,
+                DATABASES.join(", ")
+            ));
+        }
+        if !parsed.contains(&db) {
+            parsed.push(db);
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_table(table: &str) -> Result<String, String> {
+    let valid = table.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!("invalid table name '{}' (letters, digits and '_' only)", table));
+    }
+    Ok(table.to_string())
+}
@@ -0,0 +1,69 @@
+// All-or-nothing generation.
+//
+// Every command generates into memory over the disk and the changes are only
+// written out once it has finished: a command that fails halfway leaves the
+// project untouched. If writing the changes out fails, the files already
+// written are put back as they were.
+//
+// Generators mark their steps with `step`, so a failure can say where it
+// happened.
+
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::error::RvyError;
+use crate::report::Reporter;
+use crate::vfs::{Change, Disk, FileSystem, Memory, Recording};
+
+pub struct Transaction {
+    disk: Rc<dyn FileSystem>,
+    staged: Rc<Recording>,
+}
+
+impl Transaction {
+    pub fn begin() -> Self {
+        let disk: Rc<dyn FileSystem> = Rc::new(Disk);
+        let staged = Rc::new(Recording::new(Rc::new(Memory::over(disk.clone()))));
+        Self { disk, staged }
+    }
+
+    /// The filesystem generators work on.
+    pub fn fs(&self) -> Rc<dyn FileSystem> {
+        self.staged.clone()
+    }
+
+    pub fn changes(&self) -> Vec<Change> {
+        self.staged.changes()
+    }
+
+    /// Write the staged changes to disk. If one fails, the ones already
+    /// written are undone and the error is returned.
+    pub fn commit(&self, report: &Reporter) -> Result<(), RvyError> {
+        let changes: Vec<Change> = self.changes().into_iter().filter(|c| c.before != c.after).collect();
+        for (done, change) in changes.iter().enumerate() {
+            if let Err(e) = apply(&*self.disk, &change.path, change.after.as_deref()) {
+                for change in changes[..done].iter().rev() {
+                    if let Err(e) = apply(&*self.disk, &change.path, change.before.as_deref()) {
+                        report.warning(Some(&change.path.to_string_lossy()), &format!("cannot restore: {}", e));
+                    }
+                }
+                return Err(RvyError::io(change.path.display(), e));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Bring `path` to `content`, removing it for `None`
+fn apply(fs: &dyn FileSystem, path: &Path, content: Option<&str>) -> std::io::Result<()> {
+    match content {
+        Some(content) => fs.write(path, content),
+        None if fs.exists(path) => fs.remove(path),
+        None => Ok(()),
+    }
+}
+
+/// Run `f` as the step `name`, which a failure inside it reports.
+pub fn step<T>(name: &str, f: impl FnOnce() -> Result<T, RvyError>) -> Result<T, RvyError> {
+    f().map_err(|e| e.in_step(name))
+}
@@ -0,0 +1,211 @@
+// The filesystem generators read and write through, carried on `Context`.
+//
+// `Disk` is the real filesystem. `Memory` keeps files in memory, optionally
+// on top of another filesystem it reads through to: commands generate into a
+// `Memory` over the disk, so later steps see what earlier ones wrote while
+// nothing is touched until the command succeeds. `Recording` wraps another
+// filesystem and logs every change, for --diff and for writing the changes
+// out.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+pub trait FileSystem: fmt::Debug {
+    fn read(&self, path: &Path) -> io::Result<String>;
+
+    /// Whether `path` is an existing file.
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Write `content` to `path`, creating parent directories as needed.
+    fn write(&self, path: &Path, content: &str) -> io::Result<()>;
+
+    fn remove(&self, path: &Path) -> io::Result<()>;
+
+    /// Names of the files directly in `dir`, sorted. A missing directory has none.
+    fn list(&self, dir: &Path) -> io::Result<Vec<String>>;
+}
+
+#[derive(Debug, Default)]
+pub struct Disk;
+
+impl FileSystem for Disk {
+    fn read(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn write(&self, path: &Path, content: &str) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Memory {
+    // `None` marks a file removed from the filesystem underneath
+    files: RefCell<BTreeMap<PathBuf, Option<String>>>,
+    lower: Option<Rc<dyn FileSystem>>,
+}
+
+impl Memory {
+    /// Changes stay in memory; everything else is read from `lower`.
+    pub fn over(lower: Rc<dyn FileSystem>) -> Self {
+        Self { files: RefCell::default(), lower: Some(lower) }
+    }
+}
+
+impl FileSystem for Memory {
+    fn read(&self, path: &Path) -> io::Result<String> {
+        match self.files.borrow().get(&normalize(path)) {
+            Some(Some(content)) => Ok(content.clone()),
+            Some(None) => Err(not_found(path)),
+            None => match &self.lower {
+                Some(lower) => lower.read(path),
+                None => Err(not_found(path)),
+            },
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match self.files.borrow().get(&normalize(path)) {
+            Some(file) => file.is_some(),
+            None => self.lower.as_ref().is_some_and(|lower| lower.exists(path)),
+        }
+    }
+
+    fn write(&self, path: &Path, content: &str) -> io::Result<()> {
+        self.files.borrow_mut().insert(normalize(path), Some(content.to_string()));
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        if !self.exists(path) {
+            return Err(not_found(path));
+        }
+        self.files.borrow_mut().insert(normalize(path), None);
+        Ok(())
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<String>> {
+        let mut names: BTreeSet<String> = match &self.lower {
+            Some(lower) => lower.list(dir)?.into_iter().collect(),
+            None => BTreeSet::new(),
+        };
+        let dir = normalize(dir);
+        for (path, file) in self.files.borrow().iter() {
+            if path.parent() != Some(dir.as_path()) {
+                continue;
+            }
+            let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            if file.is_some() {
+                names.insert(name);
+            } else {
+                names.remove(&name);
+            }
+        }
+        Ok(names.into_iter().collect())
+    }
+}
+
+/// A file changed through a `Recording`: its content before the first
+/// change and after the last one, `None` when it didn't or no longer exists.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub path: PathBuf,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Recording {
+    inner: Rc<dyn FileSystem>,
+    changes: RefCell<Vec<Change>>,
+}
+
+impl Recording {
+    pub fn new(inner: Rc<dyn FileSystem>) -> Self {
+        Self { inner, changes: RefCell::default() }
+    }
+
+    /// Every file changed so far, in the order they were first changed.
+    pub fn changes(&self) -> Vec<Change> {
+        self.changes.borrow().clone()
+    }
+
+    fn record(&self, path: &Path, before: Option<String>, after: Option<&str>) {
+        let path = normalize(path);
+        let mut changes = self.changes.borrow_mut();
+        match changes.iter_mut().find(|change| change.path == path) {
+            Some(change) => change.after = after.map(str::to_string),
+            None => changes.push(Change { path, before, after: after.map(str::to_string) }),
+        }
+    }
+}
+
+impl FileSystem for Recording {
+    fn read(&self, path: &Path) -> io::Result<String> {
+        self.inner.read(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn write(&self, path: &Path, content: &str) -> io::Result<()> {
+        let before = self.inner.read(path).ok();
+        self.inner.write(path, content)?;
+        self.record(path, before, Some(content));
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        let before = self.inner.read(path).ok();
+        self.inner.remove(path)?;
+        self.record(path, before, None);
+        Ok(())
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<String>> {
+        self.inner.list(dir)
+    }
+}
+
+// `./src/lib.rs` and `src/lib.rs` are the same file
+fn normalize(path: &Path) -> PathBuf {
+    path.components().filter(|c| !matches!(c, Component::CurDir)).collect()
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("{} not found", path.display()))
+}
@@ -0,0 +1,280 @@
+{% from "partials/permissions.rs.tpl" import quoted %}
+use actix_web::{middleware::from_fn, web, HttpResponse};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::{{service_mod}}::{{Service}};
+use crate::{{data_mod}}::{{Data}};
+use crate::auth::{require_auth, AuthConfig{% if scopes %}, Claims{% endif %}};
+use crate::error::AppError;
+
+// NOTE: This file contains business logic and OpenAPI documentation.
+// If you modify the data model, regenerate it with:
+//   rvy regen {{name}}
+// Your changes are merged in; conflicting lines get <<<<<<< markers.
+
+/// OpenAPI documentation for {{Name}} endpoints
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_all_{{names}},
+        get_{{name}}_by_id,
+        create_{{name}},
+        update_{{name}},
+        delete_{{name}}
+    ),
+    components(schemas({{Data}})),
+    tags(
+        (name = "{{name}}", description = "{{Name}} management endpoints")
+    ),
+    modifiers(&SecurityAddon)
+)]
+pub struct {{Name}}ApiDoc;
+
+/// Add Bearer token authentication to OpenAPI spec{% if scopes %}, and the
+/// OAuth2 scopes the operations require{% endif %}
+
+struct SecurityAddon;
+
+impl utoipa::Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+{% if scopes %}
+        use utoipa::openapi::security::{
+            ClientCredentials, Flow, HttpAuthScheme, HttpBuilder, OAuth2, Scopes, SecurityScheme,
+        };
+{% else %}
+        use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+{% endif %}
+        
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build()
+                ),
+            );
+{% if scopes %}
+            // Where clients get tokens carrying these scopes
+            let token_url = std::env::var("OAUTH2_TOKEN_URL").unwrap_or_else(|_| "/oauth/token".to_string());
+            components.add_security_scheme(
+                "oauth2",
+                SecurityScheme::OAuth2(OAuth2::new([Flow::ClientCredentials(ClientCredentials::new(
+                    token_url,
+                    Scopes::from_iter([
+{% for scope in scopes %}
+                        ("{{ scope }}", "{{ Name }}: {% for op in ["list", "get", "create", "update", "delete"] if scope in permissions[op] %}{{ op }}{% if not loop.last %}, {% endif %}{% endfor %}"),
+{% endfor %}
+                    ]),
+                ))])),
+            );
+{% endif %}
+        }
+    }
+}
+
+pub struct {{Handler}} {
+    service: Arc<{{Service}}>,
+}
+
+impl {{Handler}} {
+    pub fn new(service: Arc<{{Service}}>) -> Self {
+        Self { service }
+    }
+
+    /// Register the routes on an `App`: `App::new().configure({{Handler}}::routes(service, auth))`.
+    /// Every route requires a bearer token verified by `auth`.
+    pub fn routes(service: Arc<{{Service}}>, auth: Arc<AuthConfig>) -> impl FnOnce(&mut web::ServiceConfig) {
+        move |cfg| {
+            cfg.app_data(web::Data::from(service))
+                .app_data(web::Data::from(auth))
+                .service(
+                    web::resource("/{{route}}")
+                        .wrap(from_fn(require_auth))
+                        .route(web::get().to(get_all_{{names}}))
+                        .route(web::post().to(create_{{name}})),
+                )
+                .service(
+                    web::resource("/{{route}}/{id}")
+                        .wrap(from_fn(require_auth))
+                        .route(web::get().to(get_{{name}}_by_id))
+                        .route(web::put().to(update_{{name}}))
+                        .route(web::delete().to(delete_{{name}})),
+                );
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/{{route}}",
+    responses(
+        (status = 200, description = "List all {{ names | replace("_", " ") }}", body = [{{Data}}]),
+{% if permissions.list %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
+    ),
+    security(
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.list) }}]),
+{% endif %}
+    )
+)]
+async fn get_all_{{names}}(
+    service: web::Data<{{Service}}>,
+{% if permissions.list %}
+    claims: Claims,
+{% endif %}
+) -> Result<web::Json<Vec<{{Data}}>>, AppError> {
+{% if permissions.list %}
+    claims.require(&[{{ quoted(permissions.list) }}])?;
+{% endif %}
+    let items = service.get_all().await?;
+    Ok(web::Json(items))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{{route}}/{id}",
+    responses(
+        (status = 200, description = "Get {{name}} by ID", body = {{Data}}),
+        (status = 404, description = "{{Name}} not found"),
+{% if permissions.get %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
+    ),
+    params(
+        ("id" = i64, Path, description = "{{Name}} ID")
+    ),
+    security(
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.get) }}]),
+{% endif %}
+    )
+)]
+async fn get_{{name}}_by_id(
+    service: web::Data<{{Service}}>,
+{% if permissions.get %}
+    claims: Claims,
+{% endif %}
+    id: web::Path<i64>,
+) -> Result<web::Json<{{Data}}>, AppError> {
+{% if permissions.get %}
+    claims.require(&[{{ quoted(permissions.get) }}])?;
+{% endif %}
+    let item = service.get_by_id(id.into_inner()).await?;
+    Ok(web::Json(item))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{{route}}",
+    request_body = {{Data}},
+    responses(
+        (status = 201, description = "{{Name}} created successfully", body = {{Data}}),
+        (status = 400, description = "Invalid input"),
+{% if permissions.create %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
+    ),
+    security(
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.create) }}]),
+{% endif %}
+    )
+)]
+async fn create_{{name}}(
+    service: web::Data<{{Service}}>,
+{% if permissions.create %}
+    claims: Claims,
+{% endif %}
+    data: web::Json<{{Data}}>,
+) -> Result<HttpResponse, AppError> {
+{% if permissions.create %}
+    claims.require(&[{{ quoted(permissions.create) }}])?;
+{% endif %}
+    let item = service.create(data.into_inner()).await?;
+    Ok(HttpResponse::Created().json(item))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{{route}}/{id}",
+    request_body = {{Data}},
+    responses(
+        (status = 200, description = "{{Name}} updated successfully", body = {{Data}}),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "{{Name}} not found"),
+{% if permissions.update %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
+    ),
+    params(
+        ("id" = i64, Path, description = "{{Name}} ID")
+    ),
+    security(
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.update) }}]),
+{% endif %}
+    )
+)]
+async fn update_{{name}}(
+    service: web::Data<{{Service}}>,
+{% if permissions.update %}
+    claims: Claims,
+{% endif %}
+    id: web::Path<i64>,
+    data: web::Json<{{Data}}>,
+) -> Result<web::Json<{{Data}}>, AppError> {
+{% if permissions.update %}
+    claims.require(&[{{ quoted(permissions.update) }}])?;
+{% endif %}
+    let item = service.update(id.into_inner(), data.into_inner()).await?;
+    Ok(web::Json(item))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{{route}}/{id}",
+    responses(
+        (status = 204, description = "{{Name}} deleted successfully"),
+        (status = 404, description = "{{Name}} not found"),
+{% if permissions.delete %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
+    ),
+    params(
+        ("id" = i64, Path, description = "{{Name}} ID")
+    ),
+    security(
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.delete) }}]),
+{% endif %}
+    )
+)]
+async fn delete_{{name}}(
+    service: web::Data<{{Service}}>,
+{% if permissions.delete %}
+    claims: Claims,
+{% endif %}
+    id: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+{% if permissions.delete %}
+    claims.require(&[{{ quoted(permissions.delete) }}])?;
+{% endif %}
+    service.delete(id.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
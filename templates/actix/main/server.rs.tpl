@@ -0,0 +1,32 @@
+#[actix_web::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load environment variables from .env file
+    dotenvy::dotenv().ok();
+
+    println!("🚀 Starting API Server...");
+
+    // Initialize services
+    let config = {{config_mod}}::database::DatabaseConfig::from_env();
+    let auth = Arc::new(auth::AuthConfig::from_env()?);
+    let repository_{{name}} = {{factory_mod}}::create_{{name}}_repository(&config).await?;
+    let usecase_{{name}} = Arc::new({{usecase_mod}}::{{Usecase}}::new(repository_{{name}}));
+    let service_{{name}} = Arc::new({{service_mod}}::{{Service}}::new(usecase_{{name}}));
+
+    // Built once per worker thread; one OpenAPI document per entity, all listed in Swagger UI
+    let app = move || {
+        App::new()
+            .configure({{Handler}}::routes(service_{{name}}.clone(), auth.clone()))
+            .service(
+                SwaggerUi::new("/swagger-ui/{_:.*}")
+                    .url("/api-docs/{{name}}.json", {{Name}}ApiDoc::openapi()),
+            )
+    };
+
+    let addr = "127.0.0.1:{{port}}";
+    println!("✅ Server listening on http://{}", addr);
+    println!("📚 Swagger UI available at http://{}/swagger-ui/", addr);
+
+    HttpServer::new(app).bind(addr)?.run().await?;
+
+    Ok(())
+}
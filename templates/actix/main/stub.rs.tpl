@@ -0,0 +1,22 @@
+#[actix_web::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("🚀 Starting API Server...");
+
+    // TODO: Initialize your services here
+    // Example:
+    // let config = {{config_mod}}::database::DatabaseConfig::from_env();
+    // let auth = Arc::new(auth::AuthConfig::from_env()?);
+    // let repository_{{name}} = {{factory_mod}}::create_{{name}}_repository(&config).await?;
+    // let usecase_{{name}} = Arc::new({{usecase_mod}}::{{Usecase}}::new(repository_{{name}}));
+    // let service_{{name}} = Arc::new({{service_mod}}::{{Service}}::new(usecase_{{name}}));
+
+    let app = || App::new();
+        // .configure({{Handler}}::routes(service_{{name}}.clone(), auth.clone()));
+
+    let addr = "127.0.0.1:{{port}}";
+    println!("✅ Server listening on http://{}", addr);
+
+    HttpServer::new(app).bind(addr)?.run().await?;
+
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use actix_web::{http::StatusCode, test, App};
+use serde_json::json;
+use std::sync::Arc;
+
+mod common;
+use common::{{name}}::*;
+
+#[actix_web::test]
+async fn test_get_all_{{names}}() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+
+    let request = test::TestRequest::get()
+        .uri("/{{route}}")
+        .insert_header(("authorization", bearer_token()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_get_{{name}}_by_id() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+
+    let request = test::TestRequest::get()
+        .uri("/{{route}}/1")
+        .insert_header(("authorization", bearer_token()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_create_{{name}}() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+
+    let new_{{name}} = json!({
+        "id": 3,
+{% for field in fields %}
+        "{{ field.name }}": {% if field.name == label_field %}"Test {{Name}}"{% else %}{{ field.json_sample }}{% endif %},
+{% endfor %}
+        "created_at": null,
+        "updated_at": null
+    });
+
+    let request = test::TestRequest::post()
+        .uri("/{{route}}")
+        .insert_header(("authorization", bearer_token()))
+        .set_json(&new_{{name}})
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[actix_web::test]
+async fn test_update_{{name}}() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+
+    let updated_{{name}} = json!({
+        "id": 1,
+{% for field in fields %}
+        "{{ field.name }}": {% if field.name == label_field %}"Updated {{Name}}"{% else %}{{ field.json_sample }}{% endif %},
+{% endfor %}
+        "created_at": null,
+        "updated_at": null
+    });
+
+    let request = test::TestRequest::put()
+        .uri("/{{route}}/1")
+        .insert_header(("authorization", bearer_token()))
+        .set_json(&updated_{{name}})
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_delete_{{name}}() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+
+    let request = test::TestRequest::delete()
+        .uri("/{{route}}/1")
+        .insert_header(("authorization", bearer_token()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[actix_web::test]
+async fn test_get_{{name}}_not_found() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+
+    let request = test::TestRequest::get()
+        .uri("/{{route}}/999")
+        .insert_header(("authorization", bearer_token()))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_{{names}}_require_token() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+
+    let request = test::TestRequest::get().uri("/{{route}}").to_request();
+    // The middleware's error becomes the 401 response
+    let error = test::try_call_service(&app, request).await.unwrap_err();
+
+    assert_eq!(error.as_response_error().status_code(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn test_{{names}}_reject_invalid_token() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+
+    let request = test::TestRequest::get()
+        .uri("/{{route}}")
+        .insert_header(("authorization", "Bearer not-a-token"))
+        .to_request();
+    // The middleware's error becomes the 401 response
+    let error = test::try_call_service(&app, request).await.unwrap_err();
+
+    assert_eq!(error.as_response_error().status_code(), StatusCode::UNAUTHORIZED);
+}
+{# A token without the roles the operation requires gets a 403 #}
+{% macro forbidden_test(operation, method, uri, id) %}
+#[actix_web::test]
+async fn test_{{ operation }}_without_role_is_forbidden() {
+    let service = Arc::new(create_test_service());
+    let app = test::init_service(App::new().configure({{Handler}}::routes(service, test_auth()))).await;
+{% if id %}
+
+    let {{name}} = json!({
+        "id": {{ id }},
+{% for field in fields %}
+        "{{ field.name }}": {% if field.name == label_field %}"Test {{Name}}"{% else %}{{ field.json_sample }}{% endif %},
+{% endfor %}
+        "created_at": null,
+        "updated_at": null
+    });
+{% endif %}
+
+    let request = test::TestRequest::{{ method | lower }}()
+        .uri("{{ uri }}")
+        .insert_header(("authorization", bearer_token_with(&[])))
+{% if id %}
+        .set_json(&{{name}})
+{% endif %}
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+{%- endmacro %}
+{% if permissions.list %}
+
+{{ forbidden_test("get_all_" ~ names, "GET", "/" ~ route, none) }}
+{% endif %}
+{% if permissions.get %}
+
+{{ forbidden_test("get_" ~ name ~ "_by_id", "GET", "/" ~ route ~ "/1", none) }}
+{% endif %}
+{% if permissions.create %}
+
+{{ forbidden_test("create_" ~ name, "POST", "/" ~ route, 3) }}
+{% endif %}
+{% if permissions.update %}
+
+{{ forbidden_test("update_" ~ name, "PUT", "/" ~ route ~ "/1", 1) }}
+{% endif %}
+{% if permissions.delete %}
+
+{{ forbidden_test("delete_" ~ name, "DELETE", "/" ~ route ~ "/1", none) }}
+{% endif %}
@@ -1,23 +1,23 @@
 use mongodb::{Collection, bson::{doc, to_document}, options::{FindOptions, UpdateOptions}};
 use async_trait::async_trait;
 use futures::stream::TryStreamExt;
-use crate::repository::{{name}}::{{Name}}Repository;
-use crate::data::{{name}}_data::{{Name}}Data;
+use crate::{{repository_mod}}::{{Repository}};
+use crate::{{data_mod}}::{{Data}};
 use crate::error::{Result, AppError};
 
-pub struct Mongo{{Name}}Repository {
-    collection: Collection<{{Name}}Data>,
+pub struct Mongo{{Repository}} {
+    collection: Collection<{{Data}}>,
 }
 
-impl Mongo{{Name}}Repository {
-    pub fn new(collection: Collection<{{Name}}Data>) -> Self {
+impl Mongo{{Repository}} {
+    pub fn new(collection: Collection<{{Data}}>) -> Self {
         Self { collection }
     }
 }
 
 #[async_trait]
-impl {{Name}}Repository for Mongo{{Name}}Repository {
-    async fn find_by_id(&self, id: i64) -> Result<{{Name}}Data> {
+impl {{Repository}} for Mongo{{Repository}} {
+    async fn find_by_id(&self, id: i64) -> Result<{{Data}}> {
         let filter = doc! { "id": id };
         self.collection
             .find_one(filter)
@@ -25,7 +25,7 @@ impl {{Name}}Repository for Mongo{{Name}}Repository {
             .ok_or_else(|| AppError::NotFound(format!("{{Name}} with id {} not found", id)))
     }
 
-    async fn find_all(&self) -> Result<Vec<{{Name}}Data>> {
+    async fn find_all(&self) -> Result<Vec<{{Data}}>> {
         let mut cursor = self.collection.find(doc! {}).await?;
         let mut results = Vec::new();
         
@@ -36,7 +36,7 @@ impl {{Name}}Repository for Mongo{{Name}}Repository {
         Ok(results)
     }
 
-    async fn find_all_paginated(&self, limit: i64, offset: i64) -> Result<Vec<{{Name}}Data>> {
+    async fn find_all_paginated(&self, limit: i64, offset: i64) -> Result<Vec<{{Data}}>> {
         let options = FindOptions::builder()
             .limit(limit)
             .skip(offset as u64)
@@ -52,8 +52,8 @@ impl {{Name}}Repository for Mongo{{Name}}Repository {
         Ok(results)
     }
 
-    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Name}}Data>> {
-        let filter = doc! { "name": name };
+    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Data}}>> {
+        let filter = doc! { "{{label_field}}": name };
         let mut cursor = self.collection.find(filter).await?;
         let mut results = Vec::new();
         
@@ -64,9 +64,9 @@ impl {{Name}}Repository for Mongo{{Name}}Repository {
         Ok(results)
     }
 
-    async fn search(&self, query: &str) -> Result<Vec<{{Name}}Data>> {
+    async fn search(&self, query: &str) -> Result<Vec<{{Data}}>> {
         let filter = doc! { 
-            "name": { 
+            "{{label_field}}": { 
                 "$regex": query, 
                 "$options": "i" 
             } 
@@ -86,12 +86,12 @@ impl {{Name}}Repository for Mongo{{Name}}Repository {
         Ok(count as i64)
     }
 
-    async fn save(&self, data: &{{Name}}Data) -> Result<{{Name}}Data> {
+    async fn save(&self, data: &{{Data}}) -> Result<{{Data}}> {
         self.collection.insert_one(data).await?;
         Ok(data.clone())
     }
 
-    async fn save_many(&self, data: &[{{Name}}Data]) -> Result<Vec<{{Name}}Data>> {
+    async fn save_many(&self, data: &[{{Data}}]) -> Result<Vec<{{Data}}>> {
         if data.is_empty() {
             return Ok(Vec::new());
         }
@@ -100,7 +100,7 @@ impl {{Name}}Repository for Mongo{{Name}}Repository {
         Ok(data.to_vec())
     }
 
-    async fn update(&self, id: i64, data: &{{Name}}Data) -> Result<{{Name}}Data> {
+    async fn update(&self, id: i64, data: &{{Data}}) -> Result<{{Data}}> {
         let filter = doc! { "id": id };
         
         // Properly serialize the entire data object
@@ -1,24 +1,24 @@
 use sqlx::{MySqlPool, FromRow};
 use async_trait::async_trait;
-use crate::repository::{{name}}::{{Name}}Repository;
-use crate::data::{{name}}_data::{{Name}}Data;
+use crate::{{repository_mod}}::{{Repository}};
+use crate::{{data_mod}}::{{Data}};
 use crate::error::{Result, AppError};
 
-pub struct Mysql{{Name}}Repository {
+pub struct Mysql{{Repository}} {
     pool: MySqlPool,
 }
 
-impl Mysql{{Name}}Repository {
+impl Mysql{{Repository}} {
     pub fn new(pool: MySqlPool) -> Self {
         Self { pool }
     }
 }
 
 #[async_trait]
-impl {{Name}}Repository for Mysql{{Name}}Repository {
-    async fn find_by_id(&self, id: i64) -> Result<{{Name}}Data> {
-        let row = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE id = ?"
+impl {{Repository}} for Mysql{{Repository}} {
+    async fn find_by_id(&self, id: i64) -> Result<{{Data}}> {
+        let row = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} WHERE id = ?"
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -28,9 +28,9 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
         Ok(row)
     }
 
-    async fn find_all(&self) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s ORDER BY id"
+    async fn find_all(&self) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} ORDER BY id"
         )
         .fetch_all(&self.pool)
         .await?;
@@ -38,9 +38,9 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
         Ok(rows)
     }
 
-    async fn find_all_paginated(&self, limit: i64, offset: i64) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s ORDER BY id LIMIT ? OFFSET ?"
+    async fn find_all_paginated(&self, limit: i64, offset: i64) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} ORDER BY id LIMIT ? OFFSET ?"
         )
         .bind(limit)
         .bind(offset)
@@ -50,9 +50,9 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
         Ok(rows)
     }
 
-    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE name = ?"
+    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} WHERE {{label_field}} = ?"
         )
         .bind(name)
         .fetch_all(&self.pool)
@@ -61,10 +61,10 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
         Ok(rows)
     }
 
-    async fn search(&self, query: &str) -> Result<Vec<{{Name}}Data>> {
+    async fn search(&self, query: &str) -> Result<Vec<{{Data}}>> {
         let search_pattern = format!("%{}%", query);
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE name LIKE ? ORDER BY id"
+        let rows = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} WHERE {{label_field}} LIKE ? ORDER BY id"
         )
         .bind(&search_pattern)
         .fetch_all(&self.pool)
@@ -75,7 +75,7 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
 
     async fn count(&self) -> Result<i64> {
         let (count,): (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM {{name}}s"
+            "SELECT COUNT(*) FROM {{table}}"
         )
         .fetch_one(&self.pool)
         .await?;
@@ -83,12 +83,14 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
         Ok(count)
     }
 
-    async fn save(&self, data: &{{Name}}Data) -> Result<{{Name}}Data> {
+    async fn save(&self, data: &{{Data}}) -> Result<{{Data}}> {
         let result = sqlx::query(
-            "INSERT INTO {{name}}s (name, created_at, updated_at) 
-             VALUES (?, COALESCE(?, NOW()), COALESCE(?, NOW()))"
+            "INSERT INTO {{table}} ({% for field in fields %}{{ field.name }}, {% endfor %}created_at, updated_at) 
+             VALUES ({% for field in fields %}?, {% endfor %}COALESCE(?, NOW()), COALESCE(?, NOW()))"
         )
-        .bind(&data.name)
+{% for field in fields %}
+        .bind(&data.{{ field.name }})
+{% endfor %}
         .bind(data.created_at)
         .bind(data.updated_at)
         .execute(&self.pool)
@@ -98,7 +100,7 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
         self.find_by_id(id).await
     }
 
-    async fn save_many(&self, data: &[{{Name}}Data]) -> Result<Vec<{{Name}}Data>> {
+    async fn save_many(&self, data: &[{{Data}}]) -> Result<Vec<{{Data}}>> {
         if data.is_empty() {
             return Ok(Vec::new());
         }
@@ -108,17 +110,19 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
 
         for item in data {
             let result = sqlx::query(
-                "INSERT INTO {{name}}s (name, created_at, updated_at) 
-                 VALUES (?, COALESCE(?, NOW()), COALESCE(?, NOW()))"
+                "INSERT INTO {{table}} ({% for field in fields %}{{ field.name }}, {% endfor %}created_at, updated_at) 
+                 VALUES ({% for field in fields %}?, {% endfor %}COALESCE(?, NOW()), COALESCE(?, NOW()))"
             )
-            .bind(&item.name)
+{% for field in fields %}
+            .bind(&item.{{ field.name }})
+{% endfor %}
             .bind(item.created_at)
             .bind(item.updated_at)
             .execute(&mut *tx)
             .await?;
             
             let id = result.last_insert_id() as i64;
-            let row = sqlx::query_as::<_, {{Name}}Data>("SELECT * FROM {{name}}s WHERE id = ?")
+            let row = sqlx::query_as::<_, {{Data}}>("SELECT * FROM {{table}} WHERE id = ?")
                 .bind(id)
                 .fetch_one(&mut *tx)
                 .await?;
@@ -130,13 +134,15 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
         Ok(results)
     }
 
-    async fn update(&self, id: i64, data: &{{Name}}Data) -> Result<{{Name}}Data> {
+    async fn update(&self, id: i64, data: &{{Data}}) -> Result<{{Data}}> {
         let result = sqlx::query(
-            "UPDATE {{name}}s 
-             SET name = ?, updated_at = NOW() 
+            "UPDATE {{table}} 
+             SET {% for field in fields %}{{ field.name }} = ?, {% endfor %}updated_at = NOW() 
              WHERE id = ?"
         )
-        .bind(&data.name)
+{% for field in fields %}
+        .bind(&data.{{ field.name }})
+{% endfor %}
         .bind(id)
         .execute(&self.pool)
         .await?;
@@ -149,7 +155,7 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
     }
 
     async fn delete(&self, id: i64) -> Result<()> {
-        let result = sqlx::query("DELETE FROM {{name}}s WHERE id = ?")
+        let result = sqlx::query("DELETE FROM {{table}} WHERE id = ?")
             .bind(id)
             .execute(&self.pool)
             .await?;
@@ -168,7 +174,7 @@ impl {{Name}}Repository for Mysql{{Name}}Repository {
         
         // Build placeholders for IN clause
         let placeholders = vec!["?"; ids.len()].join(", ");
-        let query = format!("DELETE FROM {{{{name}}}}s WHERE id IN ({})", placeholders);
+        let query = format!("DELETE FROM {{table}} WHERE id IN ({})", placeholders);
         
         let mut q = sqlx::query(&query);
         for id in ids {
@@ -1,24 +1,24 @@
 use sqlx::{PgPool, FromRow, postgres::PgQueryResult};
 use async_trait::async_trait;
-use crate::repository::{{name}}::{{Name}}Repository;
-use crate::data::{{name}}_data::{{Name}}Data;
+use crate::{{repository_mod}}::{{Repository}};
+use crate::{{data_mod}}::{{Data}};
 use crate::error::{Result, AppError};
 
-pub struct Postgres{{Name}}Repository {
+pub struct Postgres{{Repository}} {
     pool: PgPool,
 }
 
-impl Postgres{{Name}}Repository {
+impl Postgres{{Repository}} {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
 }
 
 #[async_trait]
-impl {{Name}}Repository for Postgres{{Name}}Repository {
-    async fn find_by_id(&self, id: i64) -> Result<{{Name}}Data> {
-        let row = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE id = $1"
+impl {{Repository}} for Postgres{{Repository}} {
+    async fn find_by_id(&self, id: i64) -> Result<{{Data}}> {
+        let row = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} WHERE id = $1"
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -28,9 +28,9 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
         Ok(row)
     }
 
-    async fn find_all(&self) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s ORDER BY id"
+    async fn find_all(&self) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} ORDER BY id"
         )
         .fetch_all(&self.pool)
         .await?;
@@ -38,9 +38,9 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
         Ok(rows)
     }
 
-    async fn find_all_paginated(&self, limit: i64, offset: i64) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s ORDER BY id LIMIT $1 OFFSET $2"
+    async fn find_all_paginated(&self, limit: i64, offset: i64) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} ORDER BY id LIMIT $1 OFFSET $2"
         )
         .bind(limit)
         .bind(offset)
@@ -50,9 +50,9 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
         Ok(rows)
     }
 
-    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE name = $1"
+    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} WHERE {{label_field}} = $1"
         )
         .bind(name)
         .fetch_all(&self.pool)
@@ -61,10 +61,10 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
         Ok(rows)
     }
 
-    async fn search(&self, query: &str) -> Result<Vec<{{Name}}Data>> {
+    async fn search(&self, query: &str) -> Result<Vec<{{Data}}>> {
         let search_pattern = format!("%{}%", query);
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE name ILIKE $1 ORDER BY id"
+        let rows = sqlx::query_as::<_, {{Data}}>(
+            "SELECT * FROM {{table}} WHERE {{label_field}} ILIKE $1 ORDER BY id"
         )
         .bind(&search_pattern)
         .fetch_all(&self.pool)
@@ -75,7 +75,7 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
 
     async fn count(&self) -> Result<i64> {
         let (count,): (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM {{name}}s"
+            "SELECT COUNT(*) FROM {{table}}"
         )
         .fetch_one(&self.pool)
         .await?;
@@ -83,13 +83,15 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
         Ok(count)
     }
 
-    async fn save(&self, data: &{{Name}}Data) -> Result<{{Name}}Data> {
-        let row = sqlx::query_as::<_, {{Name}}Data>(
-            "INSERT INTO {{name}}s (name, created_at, updated_at) 
-             VALUES ($1, COALESCE($2, NOW()), COALESCE($3, NOW())) 
+    async fn save(&self, data: &{{Data}}) -> Result<{{Data}}> {
+        let row = sqlx::query_as::<_, {{Data}}>(
+            "INSERT INTO {{table}} ({% for field in fields %}{{ field.name }}, {% endfor %}created_at, updated_at) 
+             VALUES ({% for field in fields %}${{ loop.index }}, {% endfor %}COALESCE(${{ fields | length + 1 }}, NOW()), COALESCE(${{ fields | length + 2 }}, NOW())) 
              RETURNING *"
         )
-        .bind(&data.name)
+{% for field in fields %}
+        .bind(&data.{{ field.name }})
+{% endfor %}
         .bind(data.created_at)
         .bind(data.updated_at)
         .fetch_one(&self.pool)
@@ -98,7 +100,7 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
         Ok(row)
     }
 
-    async fn save_many(&self, data: &[{{Name}}Data]) -> Result<Vec<{{Name}}Data>> {
+    async fn save_many(&self, data: &[{{Data}}]) -> Result<Vec<{{Data}}>> {
         if data.is_empty() {
             return Ok(Vec::new());
         }
@@ -107,12 +109,14 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
         let mut results = Vec::with_capacity(data.len());
 
         for item in data {
-            let row = sqlx::query_as::<_, {{Name}}Data>(
-                "INSERT INTO {{name}}s (name, created_at, updated_at) 
-                 VALUES ($1, COALESCE($2, NOW()), COALESCE($3, NOW())) 
+            let row = sqlx::query_as::<_, {{Data}}>(
+                "INSERT INTO {{table}} ({% for field in fields %}{{ field.name }}, {% endfor %}created_at, updated_at) 
+                 VALUES ({% for field in fields %}${{ loop.index }}, {% endfor %}COALESCE(${{ fields | length + 1 }}, NOW()), COALESCE(${{ fields | length + 2 }}, NOW())) 
                  RETURNING *"
             )
-            .bind(&item.name)
+{% for field in fields %}
+            .bind(&item.{{ field.name }})
+{% endfor %}
             .bind(item.created_at)
             .bind(item.updated_at)
             .fetch_one(&mut *tx)
@@ -125,14 +129,16 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
         Ok(results)
     }
 
-    async fn update(&self, id: i64, data: &{{Name}}Data) -> Result<{{Name}}Data> {
-        let row = sqlx::query_as::<_, {{Name}}Data>(
-            "UPDATE {{name}}s 
-             SET name = $1, updated_at = NOW() 
-             WHERE id = $2 
+    async fn update(&self, id: i64, data: &{{Data}}) -> Result<{{Data}}> {
+        let row = sqlx::query_as::<_, {{Data}}>(
+            "UPDATE {{table}} 
+             SET {% for field in fields %}{{ field.name }} = ${{ loop.index }}, {% endfor %}updated_at = NOW() 
+             WHERE id = ${{ fields | length + 1 }} 
              RETURNING *"
         )
-        .bind(&data.name)
+{% for field in fields %}
+        .bind(&data.{{ field.name }})
+{% endfor %}
         .bind(id)
         .fetch_optional(&self.pool)
         .await?
@@ -142,7 +148,7 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
     }
 
     async fn delete(&self, id: i64) -> Result<()> {
-        let result = sqlx::query("DELETE FROM {{name}}s WHERE id = $1")
+        let result = sqlx::query("DELETE FROM {{table}} WHERE id = $1")
             .bind(id)
             .execute(&self.pool)
             .await?;
@@ -159,7 +165,7 @@ impl {{Name}}Repository for Postgres{{Name}}Repository {
             return Ok(0);
         }
         
-        let result = sqlx::query("DELETE FROM {{name}}s WHERE id = ANY($1)")
+        let result = sqlx::query("DELETE FROM {{table}} WHERE id = ANY($1)")
             .bind(ids)
             .execute(&self.pool)
             .await?;
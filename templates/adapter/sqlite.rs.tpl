@@ -1,81 +1,98 @@
-use sqlx::{SqlitePool, FromRow};
+use sqlx::{SqlitePool, Row, sqlite::SqliteRow};
 use async_trait::async_trait;
-use crate::repository::{{name}}::{{Name}}Repository;
-use crate::data::{{name}}_data::{{Name}}Data;
+use crate::{{repository_mod}}::{{Repository}};
+use crate::{{data_mod}}::{{Data}};
 use crate::error::{Result, AppError};
 
-pub struct Sqlite{{Name}}Repository {
+pub struct Sqlite{{Repository}} {
     pool: SqlitePool,
 }
 
-impl Sqlite{{Name}}Repository {
+impl Sqlite{{Repository}} {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
 }
 
+// Rows are mapped by hand because sqlx has no SQLite codec for every field type
+// (decimals are stored as TEXT and decoded through `sqlx::types::Text`)
+fn {{name}}_from_row(row: &SqliteRow) -> Result<{{Data}}> {
+    Ok({{Data}} {
+        id: row.try_get("id")?,
+{% for field in fields %}
+{% if field.type == "decimal" %}
+        {{ field.name }}: row.try_get::<{% if field.nullable %}Option<sqlx::types::Text<rust_decimal::Decimal>>{% else %}sqlx::types::Text<rust_decimal::Decimal>{% endif %}, _>("{{ field.name }}")?{% if field.nullable %}.map(|v| v.0){% else %}.0{% endif %},
+{% else %}
+        {{ field.name }}: row.try_get("{{ field.name }}")?,
+{% endif %}
+{% endfor %}
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
 #[async_trait]
-impl {{Name}}Repository for Sqlite{{Name}}Repository {
-    async fn find_by_id(&self, id: i64) -> Result<{{Name}}Data> {
-        let row = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE id = ?"
+impl {{Repository}} for Sqlite{{Repository}} {
+    async fn find_by_id(&self, id: i64) -> Result<{{Data}}> {
+        let row = sqlx::query(
+            "SELECT * FROM {{table}} WHERE id = ?"
         )
         .bind(id)
         .fetch_optional(&self.pool)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("{{Name}} with id {} not found", id)))?;
         
-        Ok(row)
+        {{name}}_from_row(&row)
     }
 
-    async fn find_all(&self) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s ORDER BY id"
+    async fn find_all(&self) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query(
+            "SELECT * FROM {{table}} ORDER BY id"
         )
         .fetch_all(&self.pool)
         .await?;
         
-        Ok(rows)
+        rows.iter().map({{name}}_from_row).collect()
     }
 
-    async fn find_all_paginated(&self, limit: i64, offset: i64) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s ORDER BY id LIMIT ? OFFSET ?"
+    async fn find_all_paginated(&self, limit: i64, offset: i64) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query(
+            "SELECT * FROM {{table}} ORDER BY id LIMIT ? OFFSET ?"
         )
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
         .await?;
         
-        Ok(rows)
+        rows.iter().map({{name}}_from_row).collect()
     }
 
-    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Name}}Data>> {
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE name = ?"
+    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Data}}>> {
+        let rows = sqlx::query(
+            "SELECT * FROM {{table}} WHERE {{label_field}} = ?"
         )
         .bind(name)
         .fetch_all(&self.pool)
         .await?;
         
-        Ok(rows)
+        rows.iter().map({{name}}_from_row).collect()
     }
 
-    async fn search(&self, query: &str) -> Result<Vec<{{Name}}Data>> {
+    async fn search(&self, query: &str) -> Result<Vec<{{Data}}>> {
         let search_pattern = format!("%{}%", query);
-        let rows = sqlx::query_as::<_, {{Name}}Data>(
-            "SELECT * FROM {{name}}s WHERE name LIKE ? ORDER BY id"
+        let rows = sqlx::query(
+            "SELECT * FROM {{table}} WHERE {{label_field}} LIKE ? ORDER BY id"
         )
         .bind(&search_pattern)
         .fetch_all(&self.pool)
         .await?;
         
-        Ok(rows)
+        rows.iter().map({{name}}_from_row).collect()
     }
 
     async fn count(&self) -> Result<i64> {
         let (count,): (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM {{name}}s"
+            "SELECT COUNT(*) FROM {{table}}"
         )
         .fetch_one(&self.pool)
         .await?;
@@ -83,22 +100,30 @@ impl {{Name}}Repository for Sqlite{{Name}}Repository {
         Ok(count)
     }
 
-    async fn save(&self, data: &{{Name}}Data) -> Result<{{Name}}Data> {
-        let row = sqlx::query_as::<_, {{Name}}Data>(
-            "INSERT INTO {{name}}s (name, created_at, updated_at) 
-             VALUES (?, COALESCE(?, CURRENT_TIMESTAMP), COALESCE(?, CURRENT_TIMESTAMP)) 
+    async fn save(&self, data: &{{Data}}) -> Result<{{Data}}> {
+        let row = sqlx::query(
+            "INSERT INTO {{table}} ({% for field in fields %}{{ field.name }}, {% endfor %}created_at, updated_at) 
+             VALUES ({% for field in fields %}?, {% endfor %}COALESCE(?, CURRENT_TIMESTAMP), COALESCE(?, CURRENT_TIMESTAMP)) 
              RETURNING *"
         )
-        .bind(&data.name)
+{% for field in fields %}
+{% if field.type == "decimal" and field.nullable %}
+        .bind(data.{{ field.name }}.map(sqlx::types::Text))
+{% elif field.type == "decimal" %}
+        .bind(sqlx::types::Text(data.{{ field.name }}))
+{% else %}
+        .bind(&data.{{ field.name }})
+{% endif %}
+{% endfor %}
         .bind(data.created_at)
         .bind(data.updated_at)
         .fetch_one(&self.pool)
         .await?;
         
-        Ok(row)
+        {{name}}_from_row(&row)
     }
 
-    async fn save_many(&self, data: &[{{Name}}Data]) -> Result<Vec<{{Name}}Data>> {
+    async fn save_many(&self, data: &[{{Data}}]) -> Result<Vec<{{Data}}>> {
         if data.is_empty() {
             return Ok(Vec::new());
         }
@@ -107,42 +132,58 @@ impl {{Name}}Repository for Sqlite{{Name}}Repository {
         let mut results = Vec::with_capacity(data.len());
 
         for item in data {
-            let row = sqlx::query_as::<_, {{Name}}Data>(
-                "INSERT INTO {{name}}s (name, created_at, updated_at) 
-                 VALUES (?, COALESCE(?, CURRENT_TIMESTAMP), COALESCE(?, CURRENT_TIMESTAMP)) 
+            let row = sqlx::query(
+                "INSERT INTO {{table}} ({% for field in fields %}{{ field.name }}, {% endfor %}created_at, updated_at) 
+                 VALUES ({% for field in fields %}?, {% endfor %}COALESCE(?, CURRENT_TIMESTAMP), COALESCE(?, CURRENT_TIMESTAMP)) 
                  RETURNING *"
             )
-            .bind(&item.name)
+{% for field in fields %}
+{% if field.type == "decimal" and field.nullable %}
+            .bind(item.{{ field.name }}.map(sqlx::types::Text))
+{% elif field.type == "decimal" %}
+            .bind(sqlx::types::Text(item.{{ field.name }}))
+{% else %}
+            .bind(&item.{{ field.name }})
+{% endif %}
+{% endfor %}
             .bind(item.created_at)
             .bind(item.updated_at)
             .fetch_one(&mut *tx)
             .await?;
             
-            results.push(row);
+            results.push({{name}}_from_row(&row)?);
         }
 
         tx.commit().await?;
         Ok(results)
     }
 
-    async fn update(&self, id: i64, data: &{{Name}}Data) -> Result<{{Name}}Data> {
-        let row = sqlx::query_as::<_, {{Name}}Data>(
-            "UPDATE {{name}}s 
-             SET name = ?, updated_at = CURRENT_TIMESTAMP 
+    async fn update(&self, id: i64, data: &{{Data}}) -> Result<{{Data}}> {
+        let row = sqlx::query(
+            "UPDATE {{table}} 
+             SET {% for field in fields %}{{ field.name }} = ?, {% endfor %}updated_at = CURRENT_TIMESTAMP 
              WHERE id = ? 
              RETURNING *"
         )
-        .bind(&data.name)
+{% for field in fields %}
+{% if field.type == "decimal" and field.nullable %}
+        .bind(data.{{ field.name }}.map(sqlx::types::Text))
+{% elif field.type == "decimal" %}
+        .bind(sqlx::types::Text(data.{{ field.name }}))
+{% else %}
+        .bind(&data.{{ field.name }})
+{% endif %}
+{% endfor %}
         .bind(id)
         .fetch_optional(&self.pool)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("{{Name}} with id {} not found", id)))?;
         
-        Ok(row)
+        {{name}}_from_row(&row)
     }
 
     async fn delete(&self, id: i64) -> Result<()> {
-        let result = sqlx::query("DELETE FROM {{name}}s WHERE id = ?")
+        let result = sqlx::query("DELETE FROM {{table}} WHERE id = ?")
             .bind(id)
             .execute(&self.pool)
             .await?;
@@ -161,7 +202,7 @@ impl {{Name}}Repository for Sqlite{{Name}}Repository {
         
         // Build placeholders for IN clause
         let placeholders = vec!["?"; ids.len()].join(", ");
-        let query = format!("DELETE FROM {{{{name}}}}s WHERE id IN ({})", placeholders);
+        let query = format!("DELETE FROM {{table}} WHERE id IN ({})", placeholders);
         
         let mut q = sqlx::query(&query);
         for id in ids {
@@ -0,0 +1,189 @@
+// JWT bearer authentication. `require_auth` guards a handler's routes: it
+// rejects requests without a valid token and hands the token's claims to the
+// handlers, which take them as a `Claims` argument and check the roles or
+// scopes an operation needs with `Claims::require`.
+
+{% if web == "actix" %}
+use actix_web::body::MessageBody;
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::middleware::Next;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use std::future::{ready, Ready};
+{% else %}
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header::AUTHORIZATION, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+{% endif %}
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// Claims of a verified token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    /// Who the token was issued to
+    pub sub: String,
+    /// Expiry, in seconds since the Unix epoch
+    pub exp: u64,
+    /// Roles granted to the subject
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    /// OAuth2 scopes, space-separated
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub scope: String,
+}
+
+impl Claims {
+    /// Whether the token grants `permission`, as a role or a scope
+    pub fn has(&self, permission: &str) -> bool {
+        self.roles.iter().any(|role| role == permission)
+            || self.scope.split_whitespace().any(|scope| scope == permission)
+    }
+
+    /// Fails with 403 unless the token grants one of `permissions`. No
+    /// permissions means any token will do.
+    pub fn require(&self, permissions: &[&str]) -> Result<(), AppError> {
+        if permissions.is_empty() || permissions.iter().any(|permission| self.has(permission)) {
+            return Ok(());
+        }
+        Err(AppError::Forbidden(format!("Requires one of: {}", permissions.join(", "))))
+    }
+}
+
+/// How tokens are verified: the algorithm and its key, plus the issuer and
+/// audience tokens must carry, if any
+#[derive(Clone)]
+pub struct AuthConfig {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+impl AuthConfig {
+    /// HS256 tokens signed with a shared secret
+    pub fn hs256(secret: &[u8]) -> Self {
+        Self::new(DecodingKey::from_secret(secret), Algorithm::HS256)
+    }
+
+    /// RS256 tokens, checked against the issuer's RSA public key (PEM)
+    pub fn rs256(public_key_pem: &[u8]) -> Result<Self, AppError> {
+        let key = DecodingKey::from_rsa_pem(public_key_pem)
+            .map_err(|e| AppError::Internal(format!("Invalid RS256 public key: {}", e)))?;
+        Ok(Self::new(key, Algorithm::RS256))
+    }
+
+    fn new(key: DecodingKey, algorithm: Algorithm) -> Self {
+        let mut validation = Validation::new(algorithm);
+        // Only checked once an audience is required
+        validation.validate_aud = false;
+        Self { key, validation }
+    }
+
+    /// Only accept tokens issued by `issuer`
+    pub fn with_issuer(mut self, issuer: &str) -> Self {
+        self.validation.set_issuer(&[issuer]);
+        self.validation.required_spec_claims.insert("iss".to_string());
+        self
+    }
+
+    /// Only accept tokens meant for `audience`
+    pub fn with_audience(mut self, audience: &str) -> Self {
+        self.validation.set_audience(&[audience]);
+        self.validation.required_spec_claims.insert("aud".to_string());
+        self
+    }
+
+    /// Read the configuration from the environment:
+    /// - `JWT_ALGORITHM`: `HS256` (default) or `RS256`
+    /// - `JWT_SECRET`: the HS256 secret
+    /// - `JWT_PUBLIC_KEY`: the RS256 public key, PEM
+    /// - `JWT_ISSUER`, `JWT_AUDIENCE`: required `iss` and `aud` claims, when set
+    pub fn from_env() -> Result<Self, AppError> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let required = |name: &str| var(name).ok_or_else(|| AppError::Internal(format!("{} is not set", name)));
+
+        let mut config = match var("JWT_ALGORITHM").as_deref().unwrap_or("HS256") {
+            "HS256" => Self::hs256(required("JWT_SECRET")?.as_bytes()),
+            "RS256" => Self::rs256(required("JWT_PUBLIC_KEY")?.as_bytes())?,
+            other => {
+                return Err(AppError::Internal(format!("Unsupported JWT_ALGORITHM {}, use HS256 or RS256", other)));
+            }
+        };
+        if let Some(issuer) = var("JWT_ISSUER") {
+            config = config.with_issuer(&issuer);
+        }
+        if let Some(audience) = var("JWT_AUDIENCE") {
+            config = config.with_audience(&audience);
+        }
+        Ok(config)
+    }
+
+    /// Verify the value of an `Authorization: Bearer <token>` header.
+    pub fn verify(&self, authorization: Option<&str>) -> Result<Claims, AppError> {
+        let token = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| AppError::Unauthorized("Missing bearer token".to_string()))?;
+        decode::<Claims>(token, &self.key, &self.validation)
+            .map(|data| data.claims)
+            .map_err(|e| AppError::Unauthorized(format!("Invalid token: {}", e)))
+    }
+}
+{% if web == "actix" %}
+
+/// Middleware for `.wrap(from_fn(require_auth))`. The `AuthConfig` comes from
+/// the app data.
+pub async fn require_auth(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let auth = request
+        .app_data::<web::Data<AuthConfig>>()
+        .ok_or_else(|| AppError::Internal("No AuthConfig in the app data".to_string()))?;
+    let authorization = request.headers().get(AUTHORIZATION).and_then(|value| value.to_str().ok());
+    let claims = auth.verify(authorization)?;
+    request.extensions_mut().insert(claims);
+    next.call(request).await
+}
+
+impl FromRequest for Claims {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let claims = request.extensions().get::<Claims>().cloned();
+        ready(claims.ok_or_else(|| AppError::Unauthorized("Route is not behind require_auth".to_string())))
+    }
+}
+{% else %}
+
+/// Middleware for `middleware::from_fn_with_state(auth, require_auth)`.
+pub async fn require_auth(
+    State(auth): State<Arc<AuthConfig>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let authorization = request.headers().get(AUTHORIZATION).and_then(|value| value.to_str().ok());
+    let claims = auth.verify(authorization)?;
+    request.extensions_mut().insert(claims);
+    Ok(next.run(request).await)
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Claims {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Claims>()
+            .cloned()
+            .ok_or_else(|| AppError::Unauthorized("Route is not behind require_auth".to_string()))
+    }
+}
+{% endif %}
@@ -1,23 +1,35 @@
 use serde::{Deserialize, Serialize};
+{% if has_sql %}
 use sqlx::FromRow;
+{% endif %}
+{% if http %}
 use utoipa::ToSchema;
+{% endif %}
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
-pub struct {{Name}}Data {
+#[derive(Debug, Clone, Serialize, Deserialize{% if has_sql %}, FromRow{% endif %}{% if http %}, ToSchema{% endif %})]
+pub struct {{Data}} {
+{% if http %}
     #[schema(example = 1)]
+{% endif %}
     pub id: i64,
+{% for field in fields %}
+{% if http and field.name == label_field %}
     #[schema(example = "Example {{Name}}")]
-    pub name: String,
+{% endif %}
+    pub {{ field.name }}: {{ field.rust_type }},
+{% endfor %}
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
     // Add your additional fields here
 }
 
-impl {{Name}}Data {
-    pub fn new(id: i64, name: String) -> Self {
+impl {{Data}} {
+    pub fn new(id: i64{% for field in fields %}, {{ field.name }}: {{ field.rust_type }}{% endfor %}) -> Self {
         Self {
             id,
-            name,
+{% for field in fields %}
+            {{ field.name }},
+{% endfor %}
             created_at: Some(chrono::Utc::now()),
             updated_at: Some(chrono::Utc::now()),
         }
@@ -1,9 +1,14 @@
+{% if web == "actix" and http %}
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+{% elif http %}
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
+{% endif %}
 
 /// Custom error type for the application
 #[derive(Debug)]
@@ -14,8 +19,10 @@ pub enum AppError {
     NotFound(String),
     /// Validation errors
     Validation(String),
-    /// Authentication/Authorization errors
+    /// Authentication errors
     Unauthorized(String),
+    /// Authorization errors: authenticated, but lacking a role or scope
+    Forbidden(String),
     /// Internal server errors
     Internal(String),
     /// Bad request errors
@@ -29,6 +36,7 @@ impl std::fmt::Display for AppError {
             AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
             AppError::Validation(msg) => write!(f, "Validation error: {}", msg),
             AppError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             AppError::Internal(msg) => write!(f, "Internal error: {}", msg),
             AppError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
         }
@@ -37,6 +45,7 @@ impl std::fmt::Display for AppError {
 
 impl std::error::Error for AppError {}
 
+{% if http %}
 /// Error response structure for API responses
 #[derive(Serialize)]
 pub struct ErrorResponse {
@@ -46,6 +55,36 @@ pub struct ErrorResponse {
     pub details: Option<String>,
 }
 
+{% if web == "actix" %}
+impl AppError {
+    fn parts(&self) -> (StatusCode, &'static str, &str) {
+        match self {
+            AppError::Database(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR", msg),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "NOT_FOUND", msg),
+            AppError::Validation(msg) => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR", msg),
+            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", msg),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, "FORBIDDEN", msg),
+            AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR", msg),
+            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "BAD_REQUEST", msg),
+        }
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        self.parts().0
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let (status, error_type, message) = self.parts();
+        HttpResponse::build(status).json(ErrorResponse {
+            error: error_type.to_string(),
+            message: message.to_string(),
+            details: None,
+        })
+    }
+}
+{% else %}
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_type, message) = match self {
@@ -57,6 +96,7 @@ impl IntoResponse for AppError {
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "NOT_FOUND", msg),
             AppError::Validation(msg) => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR", msg),
             AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", msg),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, "FORBIDDEN", msg),
             AppError::Internal(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "INTERNAL_ERROR",
@@ -74,7 +114,10 @@ impl IntoResponse for AppError {
         (status, body).into_response()
     }
 }
+{% endif %}
+{% endif %}
 
+{% if has_sql %}
 /// Convert SQLx errors to AppError
 impl From<sqlx::Error> for AppError {
     fn from(err: sqlx::Error) -> Self {
@@ -86,6 +129,8 @@ impl From<sqlx::Error> for AppError {
     }
 }
 
+{% endif %}
+{% if "mongodb" in project_databases %}
 /// Convert MongoDB errors to AppError
 impl From<mongodb::error::Error> for AppError {
     fn from(err: mongodb::error::Error) -> Self {
@@ -93,6 +138,8 @@ impl From<mongodb::error::Error> for AppError {
     }
 }
 
+{% endif %}
+
 /// Convert serde_json errors to AppError
 impl From<serde_json::Error> for AppError {
     fn from(err: serde_json::Error) -> Self {
@@ -61,11 +61,11 @@ DATABASE_URL=sqlite://./database.db
 
 ```bash
 # Create migration
-sqlx migrate add create_{{name}}s_table
+sqlx migrate add create_{{table}}_table
 
 # Write migration SQL (example for Postgres)
-# migrations/xxx_create_{{name}}s_table.sql
-CREATE TABLE {{name}}s (
+# migrations/xxx_create_{{table}}_table.sql
+CREATE TABLE {{table}} (
     id BIGINT PRIMARY KEY,
     name VARCHAR(255) NOT NULL,
     created_at TIMESTAMP WITH TIME ZONE,
@@ -82,10 +82,10 @@ sqlx migrate run
 
 ```rust
 use std::sync::Arc;
-use config::database::DatabaseConfig;
-use factory::{{name}}_factory::create_{{name}}_repository;
-use usecase::{{name}}_usecase::{{Name}}Usecase;
-use service::{{name}}_service::{{Name}}Service;
+use {{config_mod}}::database::DatabaseConfig;
+use {{factory_mod}}::create_{{name}}_repository;
+use {{usecase_mod}}::{{Usecase}};
+use {{service_mod}}::{{Service}};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -96,8 +96,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let repository = create_{{name}}_repository(&config).await?;
     
     // Create usecase and service
-    let usecase = Arc::new({{Name}}Usecase::new(repository));
-    let service = {{Name}}Service::new(usecase);
+    let usecase = Arc::new({{Usecase}}::new(repository));
+    let service = {{Service}}::new(usecase);
 
     // Use the service
     let item = service.get_by_id(1).await?;
@@ -144,8 +144,8 @@ DATABASE_TYPE=sqlite DATABASE_URL=sqlite://./test.db cargo run
 Add your business logic in the **Usecase layer**:
 
 ```rust
-impl {{Name}}Usecase {
-    pub async fn create(&self, data: {{Name}}Data) -> Result<{{Name}}Data, Box<dyn std::error::Error>> {
+impl {{Usecase}} {
+    pub async fn create(&self, data: {{Data}}) -> Result<{{Data}}, Box<dyn std::error::Error>> {
         // Validate
         if data.name.is_empty() {
             return Err("Name cannot be empty".into());
@@ -2,50 +2,72 @@
 // Copy this to your main.rs file to get a working API server
 
 use CRATE_NAME::{
-    config::database::DatabaseConfig,
-    factory::{{name}}_factory::create_{{name}}_repository,
-    usecase::{{name}}_usecase::{{Name}}Usecase,
-    service::{{name}}_service::{{Name}}Service,
-    handler::{{name}}_handler::{{Name}}Handler,
+    auth::AuthConfig,
+    {{config_mod}}::database::DatabaseConfig,
+    {{factory_mod}}::create_{{name}}_repository,
+    {{usecase_mod}}::{{Usecase}},
+    {{service_mod}}::{{Service}},
+    {{handler_mod}}::{{Handler}},
 };
+{% if web == "actix" %}
+use actix_web::{web, App, HttpServer};
+use std::sync::Arc;
+
+#[actix_web::main]
+{% else %}
 use axum::Router;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 
 #[tokio::main]
+{% endif %}
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration from environment
     let config = DatabaseConfig::from_env();
+    let auth = Arc::new(AuthConfig::from_env()?);
     
     println!("🚀 Starting {{Name}} API Server");
     println!("📦 Database: {:?}", config.db_type);
 
     // Initialize layers using dependency injection
     let repository = create_{{name}}_repository(&config).await?;
-    let usecase = Arc::new({{Name}}Usecase::new(repository));
-    let service = Arc::new({{Name}}Service::new(usecase));
+    let usecase = Arc::new({{Usecase}}::new(repository));
+    let service = Arc::new({{Service}}::new(usecase));
 
     // Create API routes
+{% if web == "actix" %}
+    let app = move || {
+        App::new()
+            .service(web::scope("/api").configure({{Handler}}::routes(service.clone(), auth.clone())))
+            .route("/", web::get().to(|| async { "{{Name}} API Server" }))
+            .route("/health", web::get().to(|| async { "OK" }))
+    };
+{% else %}
     let app = Router::new()
-        .nest("/api", {{Name}}Handler::routes(service))
+        .nest("/api", {{Handler}}::routes(service, auth))
         .route("/", axum::routing::get(|| async { "{{Name}} API Server" }))
         .route("/health", axum::routing::get(|| async { "OK" }));
+{% endif %}
 
     // Start server
     let addr = std::env::var("SERVER_ADDRESS")
-        .unwrap_or_else(|_| "0.0.0.0:3000".to_string());
+        .unwrap_or_else(|_| "0.0.0.0:{{port}}".to_string());
     
     println!("✅ Server listening on http://{}", addr);
     println!("\n📚 API Endpoints:");
-    println!("  GET    /api/{{name}}s       - List all");
-    println!("  GET    /api/{{name}}s/:id   - Get by ID");
-    println!("  POST   /api/{{name}}s       - Create new");
-    println!("  PUT    /api/{{name}}s/:id   - Update");
-    println!("  DELETE /api/{{name}}s/:id   - Delete");
+    println!("  GET    /api/{{route}}       - List all");
+    println!("  GET    /api/{{route}}/:id   - Get by ID");
+    println!("  POST   /api/{{route}}       - Create new");
+    println!("  PUT    /api/{{route}}/:id   - Update");
+    println!("  DELETE /api/{{route}}/:id   - Delete");
     println!("\n💡 Health check: http://{}/health\n", addr);
 
+{% if web == "actix" %}
+    HttpServer::new(app).bind(&addr)?.run().await?;
+{% else %}
     let listener = TcpListener::bind(&addr).await?;
     axum::serve(listener, app).await?;
+{% endif %}
 
     Ok(())
 }
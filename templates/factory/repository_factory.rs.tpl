@@ -1,38 +1,59 @@
 use std::sync::Arc;
-use crate::config::database::{DatabaseConfig, DatabaseType};
-use crate::repository::{{name}}::{{Name}}Repository;
+use crate::{{config_mod}}::database::{DatabaseConfig, DatabaseType};
+use crate::{{repository_mod}}::{{Repository}};
 
-// Import all adapters
-use crate::adapter::{{name}}_postgres::Postgres{{Name}}Repository;
-use crate::adapter::{{name}}_mysql::Mysql{{Name}}Repository;
-use crate::adapter::{{name}}_mongodb::Mongo{{Name}}Repository;
-use crate::adapter::{{name}}_sqlite::Sqlite{{Name}}Repository;
+// Import the adapters this entity targets
+{% if "postgres" in databases %}
+use crate::{{adapter_mod}}::{{name}}_postgres::Postgres{{Repository}};
+{% endif %}
+{% if "mysql" in databases %}
+use crate::{{adapter_mod}}::{{name}}_mysql::Mysql{{Repository}};
+{% endif %}
+{% if "mongodb" in databases %}
+use crate::{{adapter_mod}}::{{name}}_mongodb::Mongo{{Repository}};
+{% endif %}
+{% if "sqlite" in databases %}
+use crate::{{adapter_mod}}::{{name}}_sqlite::Sqlite{{Repository}};
+{% endif %}
 
-/// Factory function to create {{Name}}Repository based on config
+/// Factory function to create {{Repository}} based on config
 pub async fn create_{{name}}_repository(
     config: &DatabaseConfig,
-) -> Result<Arc<dyn {{Name}}Repository>, Box<dyn std::error::Error>> {
+) -> Result<Arc<dyn {{Repository}}>, Box<dyn std::error::Error>> {
     match config.db_type {
+{% if "postgres" in databases %}
         DatabaseType::Postgres => {
             let pool = sqlx::PgPool::connect(&config.url).await?;
-            Ok(Arc::new(Postgres{{Name}}Repository::new(pool)))
+            Ok(Arc::new(Postgres{{Repository}}::new(pool)))
         }
 
+{% endif %}
+{% if "mysql" in databases %}
         DatabaseType::Mysql => {
             let pool = sqlx::MySqlPool::connect(&config.url).await?;
-            Ok(Arc::new(Mysql{{Name}}Repository::new(pool)))
+            Ok(Arc::new(Mysql{{Repository}}::new(pool)))
         }
 
+{% endif %}
+{% if "mongodb" in databases %}
         DatabaseType::Mongodb => {
             let client = mongodb::Client::with_uri_str(&config.url).await?;
             let db = client.database("mydb"); // Change database name as needed
-            let collection = db.collection("{{name}}s");
-            Ok(Arc::new(Mongo{{Name}}Repository::new(collection)))
+            let collection = db.collection("{{table}}");
+            Ok(Arc::new(Mongo{{Repository}}::new(collection)))
         }
 
+{% endif %}
+{% if "sqlite" in databases %}
         DatabaseType::Sqlite => {
             let pool = sqlx::SqlitePool::connect(&config.url).await?;
-            Ok(Arc::new(Sqlite{{Name}}Repository::new(pool)))
+            Ok(Arc::new(Sqlite{{Repository}}::new(pool)))
         }
+{% endif %}
+{% if databases | length < project_databases | length %}
+
+        // Databases this entity doesn't target still exist in DatabaseType
+        _ => Err(format!("{:?} is not supported for {{Name}}", config.db_type).into()),
+{% endif %}
     }
 }
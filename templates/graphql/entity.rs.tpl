@@ -0,0 +1,137 @@
+{% from "partials/permissions.rs.tpl" import quoted %}
+use async_graphql::{Context, {% if scopes %}Error, ErrorExtensions, {% endif %}InputObject, Object, Result, SimpleObject};
+use std::sync::Arc;
+
+use crate::{{service_mod}}::{{Service}};
+use crate::{{data_mod}}::{{Data}};
+{% if scopes %}
+use crate::auth::Claims;
+{% endif %}
+
+// NOTE: The GraphQL types mirror {{Data}}.
+// If you modify the data model, regenerate them with:
+//   rvy regen {{name}}
+
+/// {{Name}} as returned by queries and mutations
+#[derive(SimpleObject)]
+#[graphql(name = "{{Name}}")]
+pub struct {{Name}}Object {
+    pub id: i64,
+{% for field in fields %}
+    pub {{ field.name }}: {{ field.rust_type }},
+{% endfor %}
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<{{Data}}> for {{Name}}Object {
+    fn from(data: {{Data}}) -> Self {
+        Self {
+            id: data.id,
+{% for field in fields %}
+            {{ field.name }}: data.{{ field.name }},
+{% endfor %}
+            created_at: data.created_at,
+            updated_at: data.updated_at,
+        }
+    }
+}
+
+/// Fields of a {{Name}} to create or update
+#[derive(InputObject)]
+pub struct {{Name}}Input {
+{% for field in fields %}
+    pub {{ field.name }}: {{ field.rust_type }},
+{% endfor %}
+}
+
+impl {{Name}}Input {
+    fn into_data(self, id: i64) -> {{Data}} {
+        {{Data}}::new(id{% for field in fields %}, self.{{ field.name }}{% endfor %})
+    }
+}
+
+fn service<'a>(ctx: &Context<'a>) -> Result<&'a Arc<{{Service}}>> {
+    ctx.data::<Arc<{{Service}}>>()
+}
+{% if scopes %}
+
+// Fails with a FORBIDDEN error unless the request's token grants one of
+// `permissions`. The claims come from the route's `require_auth`.
+fn require(ctx: &Context<'_>, permissions: &[&str]) -> Result<()> {
+    let claims = ctx.data::<Claims>()?;
+    claims
+        .require(permissions)
+        .map_err(|e| Error::new(e.to_string()).extend_with(|_, extensions| extensions.set("code", "FORBIDDEN")))
+}
+{% endif %}
+
+#[derive(Default)]
+pub struct {{Name}}Query;
+
+#[Object]
+impl {{Name}}Query {
+    /// Get a {{ name | replace("_", " ") }} by ID
+    async fn {{name}}(&self, ctx: &Context<'_>, id: i64) -> Result<{{Name}}Object> {
+{% if permissions.get %}
+        require(ctx, &[{{ quoted(permissions.get) }}])?;
+{% endif %}
+        Ok(service(ctx)?.get_by_id(id).await?.into())
+    }
+
+{# `all_` keeps it apart from the get resolver when the plural is the singular (news, series) #}
+    /// List all {{ names | replace("_", " ") }}
+    async fn all_{{names}}(&self, ctx: &Context<'_>) -> Result<Vec<{{Name}}Object>> {
+{% if permissions.list %}
+        require(ctx, &[{{ quoted(permissions.list) }}])?;
+{% endif %}
+        let items = service(ctx)?.get_all().await?;
+        Ok(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Search {{ names | replace("_", " ") }}
+    async fn search_{{names}}(&self, ctx: &Context<'_>, query: String) -> Result<Vec<{{Name}}Object>> {
+{% if permissions.list %}
+        require(ctx, &[{{ quoted(permissions.list) }}])?;
+{% endif %}
+        let items = service(ctx)?.search(&query).await?;
+        Ok(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Number of {{ names | replace("_", " ") }}
+    async fn count_{{names}}(&self, ctx: &Context<'_>) -> Result<i64> {
+{% if permissions.list %}
+        require(ctx, &[{{ quoted(permissions.list) }}])?;
+{% endif %}
+        Ok(service(ctx)?.count().await?)
+    }
+}
+
+#[derive(Default)]
+pub struct {{Name}}Mutation;
+
+#[Object]
+impl {{Name}}Mutation {
+    async fn create_{{name}}(&self, ctx: &Context<'_>, input: {{Name}}Input) -> Result<{{Name}}Object> {
+{% if permissions.create %}
+        require(ctx, &[{{ quoted(permissions.create) }}])?;
+{% endif %}
+        Ok(service(ctx)?.create(input.into_data(0)).await?.into())
+    }
+
+    async fn update_{{name}}(&self, ctx: &Context<'_>, id: i64, input: {{Name}}Input) -> Result<{{Name}}Object> {
+{% if permissions.update %}
+        require(ctx, &[{{ quoted(permissions.update) }}])?;
+{% endif %}
+        Ok(service(ctx)?.update(id, input.into_data(id)).await?.into())
+    }
+
+    /// Returns true once the {{ name | replace("_", " ") }} is deleted
+    async fn delete_{{name}}(&self, ctx: &Context<'_>, id: i64) -> Result<bool> {
+{% if permissions.delete %}
+        require(ctx, &[{{ quoted(permissions.delete) }}])?;
+{% endif %}
+        service(ctx)?.delete(id).await?;
+        Ok(true)
+    }
+}
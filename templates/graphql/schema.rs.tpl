@@ -0,0 +1,93 @@
+// GraphQL schema merging every entity's queries and mutations.
+// Regenerated by `rvy gen graphql` and `rvy destroy`.
+
+{% if web == "actix" %}
+use actix_web::{guard, middleware::from_fn, web, HttpResponse};
+{% else %}
+use axum::{
+    extract::State,
+    middleware,
+    response::Html,
+    routing::{get, post},
+    Json, Router,
+};
+{% endif %}
+use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
+use async_graphql::{EmptySubscription, MergedObject, Schema, SchemaBuilder};
+use std::sync::Arc;
+
+use crate::auth::{require_auth, AuthConfig, Claims};
+
+{% for entity in entities %}
+use super::{{ entity.module }}::{ {{- entity.Name }}Mutation, {{ entity.Name }}Query};
+{% endfor %}
+
+#[derive(MergedObject, Default)]
+pub struct Query({% for entity in entities %}{{ entity.Name }}Query{% if not loop.last %}, {% endif %}{% endfor %});
+
+#[derive(MergedObject, Default)]
+pub struct Mutation({% for entity in entities %}{{ entity.Name }}Mutation{% if not loop.last %}, {% endif %}{% endfor %});
+
+pub type AppSchema = Schema<Query, Mutation, EmptySubscription>;
+
+/// Add each entity's service with `.data(service)`, then `.finish()`.
+pub fn schema() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
+    Schema::build(Query::default(), Mutation::default(), EmptySubscription)
+}
+{% if web == "actix" %}
+
+/// POST /graphql runs queries and requires a bearer token verified by
+/// `auth`, whose claims the resolvers get as context data. GET /graphql
+/// serves the playground.
+pub fn routes(schema: AppSchema, auth: Arc<AuthConfig>) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        cfg.app_data(web::Data::new(schema))
+            .app_data(web::Data::from(auth))
+            .service(
+                web::resource("/graphql")
+                    .guard(guard::Post())
+                    .wrap(from_fn(require_auth))
+                    .route(web::post().to(execute)),
+            )
+            .service(web::resource("/graphql").route(web::get().to(playground)));
+    }
+}
+
+async fn execute(
+    schema: web::Data<AppSchema>,
+    claims: Claims,
+    request: web::Json<async_graphql::Request>,
+) -> web::Json<async_graphql::Response> {
+    web::Json(schema.execute(request.into_inner().data(claims)).await)
+}
+
+async fn playground() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
+}
+{% else %}
+
+/// POST /graphql runs queries and requires a bearer token verified by
+/// `auth`, whose claims the resolvers get as context data. GET /graphql
+/// serves the playground.
+pub fn routes(schema: AppSchema, auth: Arc<AuthConfig>) -> Router {
+    Router::new()
+        .route("/graphql", post(execute))
+        .route_layer(middleware::from_fn_with_state(auth, require_auth))
+        .route("/graphql", get(playground))
+        .with_state(schema)
+}
+
+async fn execute(
+    State(schema): State<AppSchema>,
+    claims: Claims,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request.data(claims)).await)
+}
+
+async fn playground() -> Html<String> {
+    Html(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
+}
+{% endif %}
@@ -0,0 +1,158 @@
+{% from "partials/permissions.rs.tpl" import quoted %}
+use async_graphql::{EmptySubscription, Request, Schema, Variables};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::{{graphql_mod}}::{ {{- Name }}Mutation, {{ Name }}Query};
+use crate::{{graphql_schema_mod}}::{routes, schema};
+
+mod common;
+use common::{{name}}::*;
+
+// The entity's resolvers on their own, over the mock repository, for a
+// token granting every role they require
+async fn execute(request: impl Into<Request>) -> async_graphql::Response {
+    execute_with(&[{{ quoted(scopes) }}], request).await
+}
+
+// Same, for a token granting only `roles`
+async fn execute_with(roles: &[&str], request: impl Into<Request>) -> async_graphql::Response {
+    let schema = Schema::build({{Name}}Query, {{Name}}Mutation, EmptySubscription)
+        .data(Arc::new(create_test_service()))
+        .finish();
+    schema.execute(request.into().data(test_claims(roles))).await
+}
+
+// Variables of a create or update mutation
+fn input_variables() -> Variables {
+    let input = json!({
+{% for field in fields %}
+        "{{ field.name | camel }}": {{ field.json_sample }},
+{% endfor %}
+    });
+    Variables::from_json(json!({ "input": input }))
+}
+
+#[tokio::test]
+async fn test_graphql_all_{{names}}() {
+    let response = execute("{ all{{Names}} { id } }").await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+    assert_eq!(data["all{{Names}}"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_graphql_{{name}}() {
+    let response = execute("{ {{ name | camel }}(id: 1) { id } }").await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.data.into_json().unwrap()["{{ name | camel }}"]["id"], 1);
+}
+
+#[tokio::test]
+async fn test_graphql_{{name}}_not_found() {
+    let response = execute("{ {{ name | camel }}(id: 999) { id } }").await;
+
+    assert_eq!(response.errors.len(), 1);
+}
+
+#[tokio::test]
+async fn test_graphql_search_and_count_{{names}}() {
+    let response = execute(r#"{ search{{Names}}(query: "Test") { id } count{{Names}} }"#).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+    assert_eq!(data["search{{Names}}"].as_array().unwrap().len(), 2);
+    assert_eq!(data["count{{Names}}"], 2);
+}
+
+#[tokio::test]
+async fn test_graphql_create_{{name}}() {
+    let request = Request::new("mutation($input: {{Name}}Input!) { create{{Name}}(input: $input) { id } }")
+        .variables(input_variables());
+
+    let response = execute(request).await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+}
+
+#[tokio::test]
+async fn test_graphql_delete_{{name}}() {
+    let response = execute("mutation { delete{{Name}}(id: 1) }").await;
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.data.into_json().unwrap()["delete{{Name}}"], true);
+}
+{# A token without the roles the operation requires gets a FORBIDDEN error #}
+{% macro forbidden_test(operation, query, input) %}
+
+#[tokio::test]
+async fn test_graphql_{{ operation }}_without_role_is_forbidden() {
+{% if input %}
+    let request = Request::new("{{ query }}").variables(input_variables());
+{% else %}
+    let request = Request::new("{{ query }}");
+{% endif %}
+
+    let response = execute_with(&[], request).await;
+
+    assert_eq!(response.errors.len(), 1);
+    let code = response.errors[0].extensions.as_ref().and_then(|extensions| extensions.get("code"));
+    assert_eq!(code, Some(&async_graphql::Value::from("FORBIDDEN")));
+}
+{%- endmacro %}
+{% if permissions.list %}
+{{ forbidden_test("all_" ~ names, "{ all" ~ Names ~ " { id } }", false) }}
+{% endif %}
+{% if permissions.get %}
+{{ forbidden_test(name, "{ " ~ (name | camel) ~ "(id: 1) { id } }", false) }}
+{% endif %}
+{% if permissions.create %}
+{{ forbidden_test("create_" ~ name, "mutation($input: " ~ Name ~ "Input!) { create" ~ Name ~ "(input: $input) { id } }", true) }}
+{% endif %}
+{% if permissions.update %}
+{{ forbidden_test("update_" ~ name, "mutation($input: " ~ Name ~ "Input!) { update" ~ Name ~ "(id: 1, input: $input) { id } }", true) }}
+{% endif %}
+{% if permissions.delete %}
+{{ forbidden_test("delete_" ~ name, "mutation { delete" ~ Name ~ "(id: 1) }", false) }}
+{% endif %}
+{% if web == "actix" %}
+
+#[actix_web::test]
+async fn test_graphql_requires_token() {
+    use actix_web::{http::StatusCode, test, App};
+
+    let schema = schema().data(Arc::new(create_test_service())).finish();
+    let app = test::init_service(App::new().configure(routes(schema, test_auth()))).await;
+
+    let request = test::TestRequest::post()
+        .uri("/graphql")
+        .set_json(json!({ "query": "{ __typename }" }))
+        .to_request();
+    // The middleware's error becomes the 401 response
+    let error = test::try_call_service(&app, request).await.unwrap_err();
+
+    assert_eq!(error.as_response_error().status_code(), StatusCode::UNAUTHORIZED);
+}
+{% else %}
+
+#[tokio::test]
+async fn test_graphql_requires_token() {
+    use axum::{body::Body, http::StatusCode};
+    use tower::ServiceExt;
+
+    let schema = schema().data(Arc::new(create_test_service())).finish();
+    let app = routes(schema, test_auth());
+
+    let request = axum::http::Request::builder()
+        .method("POST")
+        .uri("/graphql")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "query": "{ __typename }" }).to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+{% endif %}
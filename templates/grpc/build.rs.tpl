@@ -0,0 +1,24 @@
+// Compiles every .proto file in proto/ into tonic clients and servers.
+// protoc comes from protoc-bin-vendored, so no system install is needed.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto");
+
+    let mut protos = Vec::new();
+    if let Ok(entries) = std::fs::read_dir("proto") {
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "proto") {
+                protos.push(path);
+            }
+        }
+    }
+    // `rvy destroy` of the last gRPC entity leaves nothing to compile
+    if protos.is_empty() {
+        return Ok(());
+    }
+    protos.sort();
+
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::configure().compile_protos(&protos, &["proto"])?;
+    Ok(())
+}
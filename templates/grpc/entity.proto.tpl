@@ -0,0 +1,43 @@
+// gRPC API for {{Name}}, generated by rvy. build.rs compiles every file in proto/.
+syntax = "proto3";
+
+package {{name}};
+
+service {{Name}}Service {
+  rpc List(List{{Name}}Request) returns (List{{Name}}Response);
+  rpc Get(Get{{Name}}Request) returns ({{Name}});
+  rpc Create({{Name}}) returns ({{Name}});
+  rpc Update(Update{{Name}}Request) returns ({{Name}});
+  rpc Delete(Delete{{Name}}Request) returns (Delete{{Name}}Response);
+}
+
+message {{Name}} {
+  int64 id = 1;
+{% for field in fields %}
+  {{ "optional " if field.nullable else "" }}{{ field.proto_type }} {{ field.name }} = {{ loop.index + 1 }};
+{% endfor %}
+  // RFC 3339 timestamps
+  optional string created_at = {{ fields | length + 2 }};
+  optional string updated_at = {{ fields | length + 3 }};
+}
+
+message List{{Name}}Request {}
+
+message List{{Name}}Response {
+  repeated {{Name}} items = 1;
+}
+
+message Get{{Name}}Request {
+  int64 id = 1;
+}
+
+message Update{{Name}}Request {
+  int64 id = 1;
+  {{Name}} item = 2;
+}
+
+message Delete{{Name}}Request {
+  int64 id = 1;
+}
+
+message Delete{{Name}}Response {}
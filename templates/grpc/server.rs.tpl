@@ -0,0 +1,157 @@
+{% from "partials/permissions.rs.tpl" import quoted %}
+use std::sync::Arc;
+use tonic::service::interceptor::{InterceptedService, Interceptor};
+use tonic::{Request, Response, Status};
+
+use crate::{{service_mod}}::{{Service}};
+use crate::{{data_mod}}::{{Data}};
+use crate::auth::{AuthConfig{% if scopes %}, Claims{% endif %}};
+
+// NOTE: The messages are generated from proto/{{name}}.proto by build.rs.
+// If you modify the data model, regenerate the .proto and this file with:
+//   rvy regen {{name}}
+
+pub mod proto {
+    tonic::include_proto!("{{name}}");
+}
+
+use proto::{{name}}_service_server::{{Name}}ServiceServer;
+
+/// gRPC front of {{Service}}: every call delegates to it, and
+/// `AppError`s become `Status` codes.
+pub struct {{Name}}Grpc {
+    service: Arc<{{Service}}>,
+}
+
+impl {{Name}}Grpc {
+    pub fn new(service: Arc<{{Service}}>) -> Self {
+        Self { service }
+    }
+
+    /// The tonic service to add to a `tonic::transport::Server`. Every call
+    /// requires a bearer token verified by `auth`.
+    pub fn server(service: Arc<{{Service}}>, auth: Arc<AuthConfig>) -> InterceptedService<{{Name}}ServiceServer<Self>, Authenticate> {
+        {{Name}}ServiceServer::with_interceptor(Self::new(service), Authenticate(auth))
+    }
+}
+
+/// Rejects calls without a valid `authorization: Bearer <token>` and hands
+/// the token's claims to the methods in the request extensions.
+#[derive(Clone)]
+pub struct Authenticate(Arc<AuthConfig>);
+
+impl Interceptor for Authenticate {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let authorization = request.metadata().get("authorization").and_then(|value| value.to_str().ok());
+        let claims = self.0.verify(authorization)?;
+        request.extensions_mut().insert(claims);
+        Ok(request)
+    }
+}
+
+#[tonic::async_trait]
+impl proto::{{name}}_service_server::{{Name}}Service for {{Name}}Grpc {
+    async fn list(
+        &self,
+        {{ "request" if permissions.list else "_request" }}: Request<proto::List{{Name}}Request>,
+    ) -> Result<Response<proto::List{{Name}}Response>, Status> {
+{% if permissions.list %}
+        require(&request, &[{{ quoted(permissions.list) }}])?;
+{% endif %}
+        let items = self.service.get_all().await?;
+        Ok(Response::new(proto::List{{Name}}Response {
+            items: items.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get(
+        &self,
+        request: Request<proto::Get{{Name}}Request>,
+    ) -> Result<Response<proto::{{Name}}>, Status> {
+{% if permissions.get %}
+        require(&request, &[{{ quoted(permissions.get) }}])?;
+{% endif %}
+        let item = self.service.get_by_id(request.into_inner().id).await?;
+        Ok(Response::new(item.into()))
+    }
+
+    async fn create(
+        &self,
+        request: Request<proto::{{Name}}>,
+    ) -> Result<Response<proto::{{Name}}>, Status> {
+{% if permissions.create %}
+        require(&request, &[{{ quoted(permissions.create) }}])?;
+{% endif %}
+        let data = {{Data}}::try_from(request.into_inner())?;
+        let item = self.service.create(data).await?;
+        Ok(Response::new(item.into()))
+    }
+
+    async fn update(
+        &self,
+        request: Request<proto::Update{{Name}}Request>,
+    ) -> Result<Response<proto::{{Name}}>, Status> {
+{% if permissions.update %}
+        require(&request, &[{{ quoted(permissions.update) }}])?;
+{% endif %}
+        let request = request.into_inner();
+        let message = request.item.ok_or_else(|| Status::invalid_argument("item is required"))?;
+        let item = self.service.update(request.id, {{Data}}::try_from(message)?).await?;
+        Ok(Response::new(item.into()))
+    }
+
+    async fn delete(
+        &self,
+        request: Request<proto::Delete{{Name}}Request>,
+    ) -> Result<Response<proto::Delete{{Name}}Response>, Status> {
+{% if permissions.delete %}
+        require(&request, &[{{ quoted(permissions.delete) }}])?;
+{% endif %}
+        self.service.delete(request.into_inner().id).await?;
+        Ok(Response::new(proto::Delete{{Name}}Response {}))
+    }
+}
+
+impl From<{{Data}}> for proto::{{Name}} {
+    fn from(item: {{Data}}) -> Self {
+        Self {
+            id: item.id,
+{% for field in fields %}
+            {{ field.name }}: {{ field.to_proto }},
+{% endfor %}
+            created_at: item.created_at.map(|at| at.to_rfc3339()),
+            updated_at: item.updated_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+impl TryFrom<proto::{{Name}}> for {{Data}} {
+    type Error = Status;
+
+    fn try_from(message: proto::{{Name}}) -> Result<Self, Status> {
+        Ok(Self {
+            id: message.id,
+{% for field in fields %}
+            {{ field.name }}: {{ field.from_proto }},
+{% endfor %}
+            created_at: message.created_at.map(|at| at.parse()).transpose().map_err(|_| invalid_field("created_at"))?,
+            updated_at: message.updated_at.map(|at| at.parse()).transpose().map_err(|_| invalid_field("updated_at"))?,
+        })
+    }
+}
+
+{% if scopes %}
+// Fails with PERMISSION_DENIED unless the call's token grants one of
+// `permissions`. The claims come from `Authenticate`.
+fn require<T>(request: &Request<T>, permissions: &[&str]) -> Result<(), Status> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .ok_or_else(|| Status::unauthenticated("Call is not behind Authenticate"))?;
+    claims.require(permissions).map_err(|e| Status::permission_denied(e.to_string()))
+}
+
+{% endif %}
+fn invalid_field(name: &str) -> Status {
+    Status::invalid_argument(format!("invalid {}", name))
+}
@@ -0,0 +1,16 @@
+/// Convert AppError to gRPC status codes
+impl From<AppError> for tonic::Status {
+    fn from(err: AppError) -> Self {
+        match err {
+            AppError::Database(msg) => tonic::Status::internal(msg),
+            AppError::NotFound(msg) => tonic::Status::not_found(msg),
+            AppError::Validation(msg) => tonic::Status::invalid_argument(msg),
+            AppError::Unauthorized(msg) => tonic::Status::unauthenticated(msg),
+{% if has_forbidden %}
+            AppError::Forbidden(msg) => tonic::Status::permission_denied(msg),
+{% endif %}
+            AppError::Internal(msg) => tonic::Status::internal(msg),
+            AppError::BadRequest(msg) => tonic::Status::invalid_argument(msg),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+{% from "partials/test_data.rs.tpl" import sample_data %}
+use hyper_util::rt::TokioIo;
+use std::sync::Arc;
+use tonic::transport::{Endpoint, Server, Uri};
+use tonic::Code;
+use tower::service_fn;
+
+use crate::{{grpc_mod}}::proto::{{name}}_service_client::{{Name}}ServiceClient;
+use crate::{{grpc_mod}}::proto::{self, Delete{{Name}}Request, Get{{Name}}Request, List{{Name}}Request};
+use crate::{{grpc_mod}}::{{Name}}Grpc;
+
+mod common;
+use common::{{name}}::*;
+
+// A client connected to the gRPC server over an in-memory pipe, no port needed
+async fn client() -> {{Name}}ServiceClient<tonic::transport::Channel> {
+    let (client_io, server_io) = tokio::io::duplex(1024);
+    let service = Arc::new(create_test_service());
+    tokio::spawn(async move {
+        Server::builder()
+            .add_service({{Name}}Grpc::server(service, test_auth()))
+            .serve_with_incoming(tokio_stream::once(Ok::<_, std::io::Error>(server_io)))
+            .await
+    });
+
+    let mut client_io = Some(client_io);
+    let channel = Endpoint::try_from("http://in-process")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let io = client_io.take().ok_or_else(|| std::io::Error::other("already connected"));
+            async move { io.map(TokioIo::new) }
+        }))
+        .await
+        .unwrap();
+    {{Name}}ServiceClient::new(channel)
+}
+
+// `message` with the `authorization` metadata of `bearer_token`
+fn authorized<T>(message: T) -> tonic::Request<T> {
+    with_token(message, bearer_token())
+}
+
+fn with_token<T>(message: T, token: String) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    request.metadata_mut().insert("authorization", token.parse().unwrap());
+    request
+}
+
+#[tokio::test]
+async fn test_grpc_list_{{names}}() {
+    let mut client = client().await;
+
+    let response = client.list(authorized(List{{Name}}Request {})).await.unwrap();
+
+    assert_eq!(response.into_inner().items.len(), 2);
+}
+
+#[tokio::test]
+async fn test_grpc_get_{{name}}() {
+    let mut client = client().await;
+
+    let response = client.get(authorized(Get{{Name}}Request { id: 1 })).await.unwrap();
+
+    assert_eq!(response.into_inner().id, 1);
+}
+
+#[tokio::test]
+async fn test_grpc_get_{{name}}_not_found() {
+    let mut client = client().await;
+
+    let status = client.get(authorized(Get{{Name}}Request { id: 999 })).await.unwrap_err();
+
+    assert_eq!(status.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_grpc_create_{{name}}() {
+    let mut client = client().await;
+    let item = {{ sample_data(3, "Test " ~ Name) | indent(4) }};
+
+    let response = client.create(authorized(proto::{{Name}}::from(item))).await.unwrap();
+
+    assert_eq!(response.into_inner().id, 3);
+}
+
+#[tokio::test]
+async fn test_grpc_delete_{{name}}() {
+    let mut client = client().await;
+
+    let response = client.delete(authorized(Delete{{Name}}Request { id: 1 })).await;
+
+    assert!(response.is_ok());
+}
+
+#[tokio::test]
+async fn test_grpc_{{names}}_require_token() {
+    let mut client = client().await;
+
+    let status = client.list(List{{Name}}Request {}).await.unwrap_err();
+
+    assert_eq!(status.code(), Code::Unauthenticated);
+}
+{# A token without the roles the call requires gets PERMISSION_DENIED #}
+{% macro forbidden_test(operation, call) %}
+
+#[tokio::test]
+async fn test_grpc_{{ operation }}_without_role_is_permission_denied() {
+    let mut client = client().await;
+
+    let status = client.{{ call }}.await.unwrap_err();
+
+    assert_eq!(status.code(), Code::PermissionDenied);
+}
+{%- endmacro %}
+{% if permissions.list %}
+{{ forbidden_test("list_" ~ names, "list(with_token(List" ~ Name ~ "Request {}, bearer_token_with(&[])))") }}
+{% endif %}
+{% if permissions.get %}
+{{ forbidden_test("get_" ~ name, "get(with_token(Get" ~ Name ~ "Request { id: 1 }, bearer_token_with(&[])))") }}
+{% endif %}
+{% if permissions.create %}
+{{ forbidden_test("create_" ~ name, "create(with_token(proto::" ~ Name ~ "::default(), bearer_token_with(&[])))") }}
+{% endif %}
+{% if permissions.update %}
+{{ forbidden_test("update_" ~ name, "update(with_token(proto::Update" ~ Name ~ "Request::default(), bearer_token_with(&[])))") }}
+{% endif %}
+{% if permissions.delete %}
+{{ forbidden_test("delete_" ~ name, "delete(with_token(Delete" ~ Name ~ "Request { id: 1 }, bearer_token_with(&[])))") }}
+{% endif %}
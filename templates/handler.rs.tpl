@@ -1,6 +1,8 @@
+{% from "partials/permissions.rs.tpl" import quoted %}
 use axum::{
     extract::{Path, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Json},
     routing::{delete, get, post, put},
     Router,
@@ -9,26 +11,27 @@ use serde_json::json;
 use std::sync::Arc;
 use utoipa::OpenApi;
 
-use crate::service::{{name}}_service::{{Name}}Service;
-use crate::data::{{name}}_data::{{Name}}Data;
+use crate::{{service_mod}}::{{Service}};
+use crate::{{data_mod}}::{{Data}};
+use crate::auth::{require_auth, AuthConfig{% if scopes %}, Claims{% endif %}};
 use crate::error::AppError;
 
 // NOTE: This file contains business logic and OpenAPI documentation.
-// If you modify the data model, you may need to regenerate this file with:
-//   rvy gen handler {{name}} --force
-// WARNING: This will overwrite any custom changes you've made!
+// If you modify the data model, regenerate it with:
+//   rvy regen {{name}}
+// Your changes are merged in; conflicting lines get <<<<<<< markers.
 
 /// OpenAPI documentation for {{Name}} endpoints
 #[derive(OpenApi)]
 #[openapi(
     paths(
-        get_all_{{name}}s,
+        get_all_{{names}},
         get_{{name}}_by_id,
         create_{{name}},
         update_{{name}},
         delete_{{name}}
     ),
-    components(schemas({{Name}}Data)),
+    components(schemas({{Data}})),
     tags(
         (name = "{{name}}", description = "{{Name}} management endpoints")
     ),
@@ -36,12 +39,20 @@ use crate::error::AppError;
 )]
 pub struct {{Name}}ApiDoc;
 
-/// Add Bearer token authentication to OpenAPI spec
+/// Add Bearer token authentication to OpenAPI spec{% if scopes %}, and the
+/// OAuth2 scopes the operations require{% endif %}
+
 struct SecurityAddon;
 
 impl utoipa::Modify for SecurityAddon {
     fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+{% if scopes %}
+        use utoipa::openapi::security::{
+            ClientCredentials, Flow, HttpAuthScheme, HttpBuilder, OAuth2, Scopes, SecurityScheme,
+        };
+{% else %}
         use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+{% endif %}
         
         if let Some(components) = openapi.components.as_mut() {
             components.add_security_scheme(
@@ -53,132 +64,214 @@ impl utoipa::Modify for SecurityAddon {
                         .build()
                 ),
             );
+{% if scopes %}
+            // Where clients get tokens carrying these scopes
+            let token_url = std::env::var("OAUTH2_TOKEN_URL").unwrap_or_else(|_| "/oauth/token".to_string());
+            components.add_security_scheme(
+                "oauth2",
+                SecurityScheme::OAuth2(OAuth2::new([Flow::ClientCredentials(ClientCredentials::new(
+                    token_url,
+                    Scopes::from_iter([
+{% for scope in scopes %}
+                        ("{{ scope }}", "{{ Name }}: {% for op in ["list", "get", "create", "update", "delete"] if scope in permissions[op] %}{{ op }}{% if not loop.last %}, {% endif %}{% endfor %}"),
+{% endfor %}
+                    ]),
+                ))])),
+            );
+{% endif %}
         }
     }
 }
 
-pub struct {{Name}}Handler {
-    service: Arc<{{Name}}Service>,
+pub struct {{Handler}} {
+    service: Arc<{{Service}}>,
 }
 
-impl {{Name}}Handler {
-    pub fn new(service: Arc<{{Name}}Service>) -> Self {
+impl {{Handler}} {
+    pub fn new(service: Arc<{{Service}}>) -> Self {
         Self { service }
     }
 
-    pub fn routes(service: Arc<{{Name}}Service>) -> Router {
+    /// Every route requires a bearer token verified by `auth`.
+    pub fn routes(service: Arc<{{Service}}>, auth: Arc<AuthConfig>) -> Router {
         Router::new()
-            .route("/{{name}}s", get(get_all_{{name}}s))
-            .route("/{{name}}s/:id", get(get_{{name}}_by_id))
-            .route("/{{name}}s", post(create_{{name}}))
-            .route("/{{name}}s/:id", put(update_{{name}}))
-            .route("/{{name}}s/:id", delete(delete_{{name}}))
+            .route("/{{route}}", get(get_all_{{names}}))
+            .route("/{{route}}/:id", get(get_{{name}}_by_id))
+            .route("/{{route}}", post(create_{{name}}))
+            .route("/{{route}}/:id", put(update_{{name}}))
+            .route("/{{route}}/:id", delete(delete_{{name}}))
+            .route_layer(middleware::from_fn_with_state(auth, require_auth))
             .with_state(service)
     }
 }
 
 #[utoipa::path(
     get,
-    path = "/{{name}}s",
+    path = "/{{route}}",
     responses(
-        (status = 200, description = "List all {{name}}s", body = [{{Name}}Data])
+        (status = 200, description = "List all {{ names | replace("_", " ") }}", body = [{{Data}}]),
+{% if permissions.list %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
     ),
     security(
-        ("bearer_auth" = [])
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.list) }}]),
+{% endif %}
     )
 )]
-async fn get_all_{{name}}s(
-    State(service): State<Arc<{{Name}}Service>>,
-) -> Result<Json<Vec<{{Name}}Data>>, AppError> {
+async fn get_all_{{names}}(
+    State(service): State<Arc<{{Service}}>>,
+{% if permissions.list %}
+    claims: Claims,
+{% endif %}
+) -> Result<Json<Vec<{{Data}}>>, AppError> {
+{% if permissions.list %}
+    claims.require(&[{{ quoted(permissions.list) }}])?;
+{% endif %}
     let items = service.get_all().await?;
     Ok(Json(items))
 }
 
 #[utoipa::path(
     get,
-    path = "/{{name}}s/{id}",
+    path = "/{{route}}/{id}",
     responses(
-        (status = 200, description = "Get {{name}} by ID", body = {{Name}}Data),
-        (status = 404, description = "{{Name}} not found")
+        (status = 200, description = "Get {{name}} by ID", body = {{Data}}),
+        (status = 404, description = "{{Name}} not found"),
+{% if permissions.get %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
     ),
     params(
         ("id" = i64, Path, description = "{{Name}} ID")
     ),
     security(
-        ("bearer_auth" = [])
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.get) }}]),
+{% endif %}
     )
 )]
 async fn get_{{name}}_by_id(
-    State(service): State<Arc<{{Name}}Service>>,
+    State(service): State<Arc<{{Service}}>>,
+{% if permissions.get %}
+    claims: Claims,
+{% endif %}
     Path(id): Path<i64>,
-) -> Result<Json<{{Name}}Data>, AppError> {
+) -> Result<Json<{{Data}}>, AppError> {
+{% if permissions.get %}
+    claims.require(&[{{ quoted(permissions.get) }}])?;
+{% endif %}
     let item = service.get_by_id(id).await?;
     Ok(Json(item))
 }
 
 #[utoipa::path(
     post,
-    path = "/{{name}}s",
-    request_body = {{Name}}Data,
+    path = "/{{route}}",
+    request_body = {{Data}},
     responses(
-        (status = 201, description = "{{Name}} created successfully", body = {{Name}}Data),
-        (status = 400, description = "Invalid input")
+        (status = 201, description = "{{Name}} created successfully", body = {{Data}}),
+        (status = 400, description = "Invalid input"),
+{% if permissions.create %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
     ),
     security(
-        ("bearer_auth" = [])
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.create) }}]),
+{% endif %}
     )
 )]
 async fn create_{{name}}(
-    State(service): State<Arc<{{Name}}Service>>,
-    Json(data): Json<{{Name}}Data>,
-) -> Result<(StatusCode, Json<{{Name}}Data>), AppError> {
+    State(service): State<Arc<{{Service}}>>,
+{% if permissions.create %}
+    claims: Claims,
+{% endif %}
+    Json(data): Json<{{Data}}>,
+) -> Result<(StatusCode, Json<{{Data}}>), AppError> {
+{% if permissions.create %}
+    claims.require(&[{{ quoted(permissions.create) }}])?;
+{% endif %}
     let item = service.create(data).await?;
     Ok((StatusCode::CREATED, Json(item)))
 }
 
 #[utoipa::path(
     put,
-    path = "/{{name}}s/{id}",
-    request_body = {{Name}}Data,
+    path = "/{{route}}/{id}",
+    request_body = {{Data}},
     responses(
-        (status = 200, description = "{{Name}} updated successfully", body = {{Name}}Data),
+        (status = 200, description = "{{Name}} updated successfully", body = {{Data}}),
         (status = 400, description = "Invalid input"),
-        (status = 404, description = "{{Name}} not found")
+        (status = 404, description = "{{Name}} not found"),
+{% if permissions.update %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
     ),
     params(
         ("id" = i64, Path, description = "{{Name}} ID")
     ),
     security(
-        ("bearer_auth" = [])
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.update) }}]),
+{% endif %}
     )
 )]
 async fn update_{{name}}(
-    State(service): State<Arc<{{Name}}Service>>,
+    State(service): State<Arc<{{Service}}>>,
+{% if permissions.update %}
+    claims: Claims,
+{% endif %}
     Path(id): Path<i64>,
-    Json(data): Json<{{Name}}Data>,
-) -> Result<Json<{{Name}}Data>, AppError> {
+    Json(data): Json<{{Data}}>,
+) -> Result<Json<{{Data}}>, AppError> {
+{% if permissions.update %}
+    claims.require(&[{{ quoted(permissions.update) }}])?;
+{% endif %}
     let item = service.update(id, data).await?;
     Ok(Json(item))
 }
 
 #[utoipa::path(
     delete,
-    path = "/{{name}}s/{id}",
+    path = "/{{route}}/{id}",
     responses(
         (status = 204, description = "{{Name}} deleted successfully"),
-        (status = 404, description = "{{Name}} not found")
+        (status = 404, description = "{{Name}} not found"),
+{% if permissions.delete %}
+        (status = 403, description = "Token lacks a required role or scope"),
+{% endif %}
+        (status = 401, description = "Missing or invalid token")
     ),
     params(
         ("id" = i64, Path, description = "{{Name}} ID")
     ),
     security(
-        ("bearer_auth" = [])
+        ("bearer_auth" = []),
+{% if scopes %}
+        ("oauth2" = [{{ quoted(permissions.delete) }}]),
+{% endif %}
     )
 )]
 async fn delete_{{name}}(
-    State(service): State<Arc<{{Name}}Service>>,
+    State(service): State<Arc<{{Service}}>>,
+{% if permissions.delete %}
+    claims: Claims,
+{% endif %}
     Path(id): Path<i64>,
 ) -> Result<StatusCode, AppError> {
+{% if permissions.delete %}
+    claims.require(&[{{ quoted(permissions.delete) }}])?;
+{% endif %}
     service.delete(id).await?;
     Ok(StatusCode::NO_CONTENT)
 }
@@ -1,17 +1,17 @@
 use utoipa::OpenApi;
-use crate::data::{{name}}_data::{{Name}}Data;
+use crate::{{data_mod}}::{{Data}};
 
 /// OpenAPI documentation for {{Name}} endpoints
 #[derive(OpenApi)]
 #[openapi(
     paths(
-        crate::handler::{{name}}_handler::get_all_{{name}}s,
-        crate::handler::{{name}}_handler::get_{{name}}_by_id,
-        crate::handler::{{name}}_handler::create_{{name}},
-        crate::handler::{{name}}_handler::update_{{name}},
-        crate::handler::{{name}}_handler::delete_{{name}}
+        crate::{{handler_mod}}::get_all_{{names}},
+        crate::{{handler_mod}}::get_{{name}}_by_id,
+        crate::{{handler_mod}}::create_{{name}},
+        crate::{{handler_mod}}::update_{{name}},
+        crate::{{handler_mod}}::delete_{{name}}
     ),
-    components(schemas({{Name}}Data)),
+    components(schemas({{Data}})),
     tags(
         (name = "{{name}}", description = "{{Name}} management endpoints")
     )
@@ -0,0 +1,23 @@
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("🚀 Starting API Server...");
+
+    // TODO: Initialize your services here
+    // Example:
+    // let config = {{config_mod}}::database::DatabaseConfig::from_env();
+    // let auth = Arc::new(auth::AuthConfig::from_env()?);
+    // let repository_{{name}} = {{factory_mod}}::create_{{name}}_repository(&config).await?;
+    // let usecase_{{name}} = Arc::new({{usecase_mod}}::{{Usecase}}::new(repository_{{name}}));
+    // let service_{{name}} = Arc::new({{service_mod}}::{{Service}}::new(usecase_{{name}}));
+
+    let app = Router::new();
+        // .merge({{Handler}}::routes(service_{{name}}, auth));
+
+    let addr = "127.0.0.1:{{port}}";
+    println!("✅ Server listening on http://{}", addr);
+
+    let listener = TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
@@ -9,17 +9,19 @@ use mongodb::{
     Client, Database,
 };
 
-pub async fn create_{{name}}s_collection(db: &Database) -> Result<(), mongodb::error::Error> {
+pub async fn create_{{names}}_collection(db: &Database) -> Result<(), mongodb::error::Error> {
     // Create collection with validation schema
     let validator = doc! {
         "$jsonSchema": doc! {
             "bsonType": "object",
-            "required": ["name", "created_at", "updated_at"],
+            "required": [{% for field in fields if not field.nullable %}"{{ field.name }}", {% endfor %}"created_at", "updated_at"],
             "properties": doc! {
-                "name": doc! {
-                    "bsonType": "string",
-                    "description": "Name field is required and must be a string"
+{% for field in fields %}
+                "{{ field.name }}": doc! {
+                    "bsonType": {% if field.nullable %}["{{ field.bson_type }}", "null"]{% else %}"{{ field.bson_type }}"{% endif %},
+                    "description": "{{ field.name }} field"
                 },
+{% endfor %}
                 "created_at": doc! {
                     "bsonType": "date",
                     "description": "Creation timestamp"
@@ -39,29 +41,29 @@ pub async fn create_{{name}}s_collection(db: &Database) -> Result<(), mongodb::e
         .build();
 
     // Create collection (if it doesn't exist)
-    match db.create_collection("{{name}}s", options).await {
-        Ok(_) => println!("Collection '{{name}}s' created successfully"),
+    match db.create_collection("{{table}}", options).await {
+        Ok(_) => println!("Collection '{{table}}' created successfully"),
         Err(e) if e.to_string().contains("already exists") => {
-            println!("Collection '{{name}}s' already exists")
+            println!("Collection '{{table}}' already exists")
         }
         Err(e) => return Err(e),
     }
 
-    // Create index on name field
+    // Create index on {{label_field}} field
     let index_model = mongodb::IndexModel::builder()
-        .keys(doc! { "name": 1 })
+        .keys(doc! { "{{label_field}}": 1 })
         .options(
             IndexOptions::builder()
-                .name("idx_{{name}}s_name".to_string())
+                .name("idx_{{table}}_{{label_field}}".to_string())
                 .build(),
         )
         .build();
 
-    db.collection::<Document>("{{name}}s")
+    db.collection::<Document>("{{table}}")
         .create_index(index_model, None)
         .await?;
 
-    println!("Index 'idx_{{name}}s_name' created successfully");
+    println!("Index 'idx_{{table}}_{{label_field}}' created successfully");
 
     Ok(())
 }
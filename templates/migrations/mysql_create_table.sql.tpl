@@ -1,10 +1,12 @@
--- Migration: Create {{name}}s table (MySQL)
+-- Migration: Create {{table}} table (MySQL)
 -- Generated by rvy
 
-CREATE TABLE IF NOT EXISTS {{name}}s (
+CREATE TABLE IF NOT EXISTS {{table}} (
     id BIGINT AUTO_INCREMENT PRIMARY KEY,
-    name VARCHAR(255) NOT NULL,
+{% for field in fields %}
+    {{ field.name }} {{ field.mysql_type }}{% if not field.nullable %} NOT NULL{% endif %}{% if field.unique %} UNIQUE{% endif %}{% if field.default is not none %} DEFAULT {{ field.default }}{% endif %},
+{% endfor %}
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-    INDEX idx_{{name}}s_name (name)
+    INDEX idx_{{table}}_{{label_field}} ({{label_field}})
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
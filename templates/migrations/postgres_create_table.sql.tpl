@@ -1,18 +1,20 @@
--- Migration: Create {{name}}s table (PostgreSQL)
+-- Migration: Create {{table}} table (PostgreSQL)
 -- Generated by rvy
 
-CREATE TABLE IF NOT EXISTS {{name}}s (
+CREATE TABLE IF NOT EXISTS {{table}} (
     id BIGSERIAL PRIMARY KEY,
-    name VARCHAR(255) NOT NULL,
+{% for field in fields %}
+    {{ field.name }} {{ field.postgres_type }}{% if not field.nullable %} NOT NULL{% endif %}{% if field.unique %} UNIQUE{% endif %}{% if field.default is not none %} DEFAULT {{ field.default }}{% endif %},
+{% endfor %}
     created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
 );
 
--- Create index on name for faster lookups
-CREATE INDEX IF NOT EXISTS idx_{{name}}s_name ON {{name}}s(name);
+-- Create index on {{label_field}} for faster lookups
+CREATE INDEX IF NOT EXISTS idx_{{table}}_{{label_field}} ON {{table}}({{label_field}});
 
 -- Create updated_at trigger
-CREATE OR REPLACE FUNCTION update_{{name}}s_updated_at()
+CREATE OR REPLACE FUNCTION update_{{table}}_updated_at()
 RETURNS TRIGGER AS $$
 BEGIN
     NEW.updated_at = CURRENT_TIMESTAMP;
@@ -20,7 +22,7 @@ BEGIN
 END;
 $$ LANGUAGE plpgsql;
 
-CREATE TRIGGER trigger_{{name}}s_updated_at
-    BEFORE UPDATE ON {{name}}s
+CREATE TRIGGER trigger_{{table}}_updated_at
+    BEFORE UPDATE ON {{table}}
     FOR EACH ROW
-    EXECUTE FUNCTION update_{{name}}s_updated_at();
+    EXECUTE FUNCTION update_{{table}}_updated_at();
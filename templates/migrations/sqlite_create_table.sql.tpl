@@ -1,20 +1,22 @@
--- Migration: Create {{name}}s table (SQLite)
+-- Migration: Create {{table}} table (SQLite)
 -- Generated by rvy
 
-CREATE TABLE IF NOT EXISTS {{name}}s (
+CREATE TABLE IF NOT EXISTS {{table}} (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
-    name TEXT NOT NULL,
+{% for field in fields %}
+    {{ field.name }} {{ field.sqlite_type }}{% if not field.nullable %} NOT NULL{% endif %}{% if field.unique %} UNIQUE{% endif %}{% if field.default is not none %} DEFAULT {{ field.default }}{% endif %},
+{% endfor %}
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE INDEX IF NOT EXISTS idx_{{name}}s_name ON {{name}}s(name);
+CREATE INDEX IF NOT EXISTS idx_{{table}}_{{label_field}} ON {{table}}({{label_field}});
 
 -- Trigger to auto-update updated_at
-CREATE TRIGGER IF NOT EXISTS update_{{name}}s_updated_at
-    AFTER UPDATE ON {{name}}s
+CREATE TRIGGER IF NOT EXISTS update_{{table}}_updated_at
+    AFTER UPDATE ON {{table}}
     FOR EACH ROW
 BEGIN
-    UPDATE {{name}}s SET updated_at = CURRENT_TIMESTAMP
+    UPDATE {{table}} SET updated_at = CURRENT_TIMESTAMP
     WHERE id = NEW.id;
 END;
@@ -0,0 +1,2 @@
+{# Roles or scopes an operation requires, from rvy.entities.toml #}
+{% macro quoted(permissions) %}{% for permission in permissions %}"{{ permission }}"{% if not loop.last %}, {% endif %}{% endfor %}{% endmacro %}
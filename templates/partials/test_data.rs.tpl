@@ -0,0 +1,93 @@
+{# Shared test fixtures for the generated unit and integration tests #}
+{% macro sample_data(id, label) %}
+{{Data}} {
+    id: {{ id }},
+{% for field in fields %}
+    {{ field.name }}: {% if field.name == label_field %}"{{ label }}".to_string(){% else %}{{ field.sample }}{% endif %},
+{% endfor %}
+    created_at: Some(chrono::Utc::now()),
+    updated_at: Some(chrono::Utc::now()),
+}
+{%- endmacro %}
+
+{% macro mock_repository(vis) %}
+{{ vis }}struct Mock{{Repository}} {
+    data: Vec<{{Data}}>,
+}
+
+impl Mock{{Repository}} {
+    {{ vis }}fn new() -> Self {
+        Self {
+            data: vec![
+                {{ sample_data(1, "Test " ~ Name ~ " 1") | indent(16) }},
+                {{ sample_data(2, "Test " ~ Name ~ " 2") | indent(16) }},
+            ],
+        }
+    }
+}
+
+#[async_trait]
+impl {{Repository}} for Mock{{Repository}} {
+    async fn find_by_id(&self, id: i64) -> Result<{{Data}}> {
+        self.data
+            .iter()
+            .find(|d| d.id == id)
+            .cloned()
+            .ok_or_else(|| AppError::NotFound(format!("{{Name}} with id {} not found", id)))
+    }
+
+    async fn find_all(&self) -> Result<Vec<{{Data}}>> {
+        Ok(self.data.clone())
+    }
+
+    async fn find_all_paginated(&self, _limit: i64, _offset: i64) -> Result<Vec<{{Data}}>> {
+        Ok(self.data.clone())
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Vec<{{Data}}>> {
+        Ok(self.data.iter()
+            .filter(|d| d.{{label_field}} == name)
+            .cloned()
+            .collect())
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<{{Data}}>> {
+        Ok(self.data.iter()
+            .filter(|d| d.{{label_field}}.contains(query))
+            .cloned()
+            .collect())
+    }
+
+    async fn count(&self) -> Result<i64> {
+        Ok(self.data.len() as i64)
+    }
+
+    async fn save(&self, data: &{{Data}}) -> Result<{{Data}}> {
+        Ok(data.clone())
+    }
+
+    async fn save_many(&self, data: &[{{Data}}]) -> Result<Vec<{{Data}}>> {
+        Ok(data.to_vec())
+    }
+
+    async fn update(&self, id: i64, data: &{{Data}}) -> Result<{{Data}}> {
+        let mut updated = data.clone();
+        updated.id = id;
+        Ok(updated)
+    }
+
+    async fn delete(&self, _id: i64) -> Result<()> {
+        Ok(())
+    }
+
+    async fn delete_many(&self, _ids: &[i64]) -> Result<u64> {
+        Ok(_ids.len() as u64)
+    }
+}
+
+{{ vis }}fn create_test_service() -> {{Service}} {
+    let repository = Arc::new(Mock{{Repository}}::new());
+    let usecase = Arc::new({{Usecase}}::new(repository));
+    {{Service}}::new(usecase)
+}
+{%- endmacro %}
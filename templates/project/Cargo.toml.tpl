@@ -4,24 +4,53 @@ version = "0.1.0"
 edition = "2021"
 
 [dependencies]
+{% if kind != "lib" and kind != "minimal" %}
 tokio = { version = "1", features = ["full"] }
+{% endif %}
+{% if web == "actix" and http %}
+actix-web = "4"
+{% elif http %}
 axum = "0.7"
+{% endif %}
+{% if kind == "cli" %}
+clap = { version = "4", features = ["derive"] }
+{% endif %}
 serde = { version = "1.0", features = ["derive"] }
 serde_json = "1.0"
 async-trait = "0.1"
 chrono = { version = "0.4", features = ["serde"] }
+{% if kind != "lib" and kind != "minimal" %}
 dotenvy = "0.15"
+{% endif %}
+{% if http %}
 
 # OpenAPI/Swagger documentation
+{% if web == "actix" %}
+utoipa = { version = "5", features = ["actix_extras", "chrono"] }
+utoipa-swagger-ui = { version = "8", features = ["actix-web"] }
+{% else %}
 utoipa = { version = "5", features = ["axum_extras", "chrono"] }
 utoipa-swagger-ui = { version = "8", features = ["axum"] }
+{% endif %}
+{% endif %}
+{% if project_databases %}
 
-# Database dependencies - uncomment the ones you need
-sqlx = { version = "0.8", features = ["runtime-tokio-rustls", "postgres", "mysql", "sqlite", "chrono"] }
+# Database dependencies for: {{ project_databases | join(", ") }}
+{% endif %}
+{% if has_sql %}
+sqlx = { version = "0.8", features = ["runtime-tokio-rustls", {% for db in project_databases if db != "mongodb" %}"{{ db }}", {% endfor %}"chrono"] }
+{% endif %}
+{% if "mongodb" in project_databases %}
 mongodb = "3.1"
 futures = "0.3"
+{% endif %}
 
 [dev-dependencies]
 # Testing dependencies
+{% if kind == "lib" or kind == "minimal" %}
+tokio = { version = "1", features = ["full"] }
+{% endif %}
 tokio-test = "0.4"
+{% if http and web == "axum" %}
 tower = "0.5"
+{% endif %}
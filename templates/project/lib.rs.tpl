@@ -1,12 +1,7 @@
 pub mod error;
-pub mod service;
-pub mod usecase;
-pub mod repository;
-pub mod data;
-pub mod adapter;
-pub mod config;
-pub mod factory;
-pub mod handler;
+{% for module in project_layers %}
+pub mod {{ module }};
+{% endfor %}
 
 // Re-export common types
 pub use service::*;
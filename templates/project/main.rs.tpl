@@ -1,12 +1,79 @@
 mod error;
-mod service;
-mod usecase;
-mod repository;
-mod data;
-mod adapter;
-mod config;
-mod factory;
-mod handler;
+{% for module in project_layers %}
+mod {{ module }};
+{% endfor %}
+{% if kind == "worker" %}
+
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv().ok();
+
+    let every = std::env::var("WORKER_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(60);
+    let mut interval = tokio::time::interval(Duration::from_secs(every));
+
+    println!("🚀 {{Name}} worker running every {}s (Ctrl+C to stop)", every);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(e) = run_once().await {
+                    eprintln!("❌ Job failed: {}", e);
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    println!("👋 Worker stopped");
+    Ok(())
+}
+
+/// One pass of the job. Build your services here, e.g. after
+/// `rvy gen-all <entity-name>`, and call them.
+async fn run_once() -> Result<(), Box<dyn std::error::Error>> {
+    println!("⏱  Tick");
+    Ok(())
+}
+{% elif kind == "cli" %}
+
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(name = "{{package_name}}", about = "{{Name}} command-line tool")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check that the tool is set up
+    Check,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv().ok();
+
+    match Cli::parse().command {
+        Command::Check => println!("✓ {{Name}} is ready. Add commands for your entities after `rvy gen-all <entity-name>`."),
+    }
+
+    Ok(())
+}
+{% elif kind == "minimal" %}
+
+fn main() {
+    println!("🚀 Welcome to {{Name}}!");
+    println!("\n📝 Generate your first entity with:");
+    println!("   rvy gen-all <entity-name>");
+    println!("\n✨ This will generate the service, usecase, repository and data model");
+}
+{% else %}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -28,3 +95,4 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+{% endif %}
@@ -0,0 +1,71 @@
+# rvy project configuration. Every key is optional; commented values are the defaults.
+
+# What the project is (set with `rvy new project --template`): api, worker, cli, lib
+# or minimal. Decides which layers entities get.
+kind = "{{ kind }}"
+{% if http %}
+
+# HTTP framework of handlers, the server and integration tests (set with
+# `rvy new project --web`): axum or actix.
+web = "{{ web }}"
+{% endif %}
+
+# Databases this project supports (set with `rvy new project --db`). Entities get
+# adapters, factory arms and migrations for these unless rvy.entities.toml narrows them.
+{% if project_databases %}
+databases = [{% for db in project_databases %}"{{ db }}"{% if not loop.last %}, {% endif %}{% endfor %}]
+{% else %}
+# (none: {{ kind }} projects have no database code)
+{% endif %}
+
+# Line in each layer's mod.rs before which new `pub mod` declarations are inserted
+# mod_marker = "// Add your modules here"
+
+# [server]
+# port = 3000
+# grpc_port = 50051   # for `rvy gen grpc`
+
+# [paths]
+# migrations = "migrations"
+# tests = "tests"
+# examples = "examples"
+# docs = "docs"
+
+# Layer directories (module directories under src/) and file/type suffixes.
+# Adapters are named `<entity>_<db>.rs`, the database config lives in
+# `<config dir>/database.rs`, and factories only take a file suffix.
+#
+# [layers.service]
+# dir = "src/service"
+# file_suffix = "_service"
+# type_suffix = "Service"
+#
+# [layers.usecase]
+# dir = "src/usecase"
+# file_suffix = "_usecase"
+# type_suffix = "Usecase"
+#
+# [layers.repository]
+# dir = "src/repository"
+# file_suffix = ""
+# type_suffix = "Repository"
+#
+# [layers.data]
+# dir = "src/data"
+# file_suffix = "_data"
+# type_suffix = "Data"
+#
+# [layers.handler]
+# dir = "src/handler"
+# file_suffix = "_handler"
+# type_suffix = "Handler"
+#
+# [layers.factory]
+# dir = "src/factory"
+# file_suffix = "_factory"
+#
+# [layers.adapter]
+# dir = "src/adapter"
+#
+# [layers.config]
+# dir = "src/config"
@@ -1,30 +1,38 @@
 use std::sync::Arc;
-use crate::usecase::{{name}}_usecase::{{Name}}Usecase;
-use crate::data::{{name}}_data::{{Name}}Data;
+use crate::{{usecase_mod}}::{{Usecase}};
+use crate::{{data_mod}}::{{Data}};
 use crate::error::{AppError, Result};
 
-pub struct {{Name}}Service {
-    usecase: Arc<{{Name}}Usecase>,
+pub struct {{Service}} {
+    usecase: Arc<{{Usecase}}>,
 }
 
-impl {{Name}}Service {
-    pub fn new(usecase: Arc<{{Name}}Usecase>) -> Self {
+impl {{Service}} {
+    pub fn new(usecase: Arc<{{Usecase}}>) -> Self {
         Self { usecase }
     }
 
-    pub async fn get_by_id(&self, id: i64) -> Result<{{Name}}Data> {
+    pub async fn get_by_id(&self, id: i64) -> Result<{{Data}}> {
         self.usecase.get_by_id(id).await
     }
 
-    pub async fn get_all(&self) -> Result<Vec<{{Name}}Data>> {
+    pub async fn get_all(&self) -> Result<Vec<{{Data}}>> {
         self.usecase.get_all().await
     }
 
-    pub async fn create(&self, data: {{Name}}Data) -> Result<{{Name}}Data> {
+    pub async fn search(&self, query: &str) -> Result<Vec<{{Data}}>> {
+        self.usecase.search(query).await
+    }
+
+    pub async fn count(&self) -> Result<i64> {
+        self.usecase.count().await
+    }
+
+    pub async fn create(&self, data: {{Data}}) -> Result<{{Data}}> {
         self.usecase.create(data).await
     }
 
-    pub async fn update(&self, id: i64, data: {{Name}}Data) -> Result<{{Name}}Data> {
+    pub async fn update(&self, id: i64, data: {{Data}}) -> Result<{{Data}}> {
         self.usecase.update(id, data).await
     }
 
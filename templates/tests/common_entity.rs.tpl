@@ -0,0 +1,47 @@
+{% from "partials/test_data.rs.tpl" import mock_repository %}
+{% from "partials/permissions.rs.tpl" import quoted %}
+pub mod {{name}} {
+    use std::sync::Arc;
+    use async_trait::async_trait;
+
+    pub use crate::{{service_mod}}::{{Service}};
+    pub use crate::{{usecase_mod}}::{{Usecase}};
+    pub use crate::{{repository_mod}}::{{Repository}};
+    pub use crate::{{data_mod}}::{{Data}};
+    pub use crate::{{handler_mod}}::{{Handler}};
+    pub use crate::error::{Result, AppError};
+    pub use crate::auth::{AuthConfig, Claims};
+
+    pub const TEST_SECRET: &[u8] = b"test-secret";
+
+    // Verifies the tokens `bearer_token` mints
+    pub fn test_auth() -> Arc<AuthConfig> {
+        Arc::new(AuthConfig::hs256(TEST_SECRET))
+    }
+
+    // `Authorization` header value with an HS256 token valid for an hour,
+    // granting every role the {{name}} operations require
+    pub fn bearer_token() -> String {
+        bearer_token_with(&[{{ quoted(scopes) }}])
+    }
+
+    // Same, granting only `roles`
+    pub fn bearer_token_with(roles: &[&str]) -> String {
+        let key = jsonwebtoken::EncodingKey::from_secret(TEST_SECRET);
+        let token = jsonwebtoken::encode(&jsonwebtoken::Header::default(), &test_claims(roles), &key).unwrap();
+        format!("Bearer {}", token)
+    }
+
+    // Claims of a token valid for an hour, granting `roles`
+    pub fn test_claims(roles: &[&str]) -> Claims {
+        Claims {
+            sub: "test-user".to_string(),
+            exp: (chrono::Utc::now() + chrono::Duration::hours(1)).timestamp() as u64,
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+            scope: String::new(),
+        }
+    }
+
+    // Mock repository for integration tests
+    {{ mock_repository("pub ") | indent(4) }}
+}
@@ -7,18 +7,19 @@ use serde_json::json;
 use std::sync::Arc;
 
 mod common;
-use common::*;
+use common::{{name}}::*;
 
 #[tokio::test]
-async fn test_get_all_{{name}}s() {
+async fn test_get_all_{{names}}() {
     let service = Arc::new(create_test_service());
-    let app = {{Name}}Handler::routes(service);
+    let app = {{Handler}}::routes(service, test_auth());
 
     let response = app
         .clone()
         .oneshot(
             Request::builder()
-                .uri("/{{name}}s")
+                .uri("/{{route}}")
+                .header("authorization", bearer_token())
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -31,13 +32,14 @@ async fn test_get_all_{{name}}s() {
 #[tokio::test]
 async fn test_get_{{name}}_by_id() {
     let service = Arc::new(create_test_service());
-    let app = {{Name}}Handler::routes(service);
+    let app = {{Handler}}::routes(service, test_auth());
 
     let response = app
         .clone()
         .oneshot(
             Request::builder()
-                .uri("/{{name}}s/1")
+                .uri("/{{route}}/1")
+                .header("authorization", bearer_token())
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -50,11 +52,13 @@ async fn test_get_{{name}}_by_id() {
 #[tokio::test]
 async fn test_create_{{name}}() {
     let service = Arc::new(create_test_service());
-    let app = {{Name}}Handler::routes(service);
+    let app = {{Handler}}::routes(service, test_auth());
 
     let new_{{name}} = json!({
         "id": 3,
-        "name": "Test {{Name}}",
+{% for field in fields %}
+        "{{ field.name }}": {% if field.name == label_field %}"Test {{Name}}"{% else %}{{ field.json_sample }}{% endif %},
+{% endfor %}
         "created_at": null,
         "updated_at": null
     });
@@ -64,7 +68,8 @@ async fn test_create_{{name}}() {
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/{{name}}s")
+                .uri("/{{route}}")
+                .header("authorization", bearer_token())
                 .header("content-type", "application/json")
                 .body(Body::from(serde_json::to_string(&new_{{name}}).unwrap()))
                 .unwrap(),
@@ -78,11 +83,13 @@ async fn test_create_{{name}}() {
 #[tokio::test]
 async fn test_update_{{name}}() {
     let service = Arc::new(create_test_service());
-    let app = {{Name}}Handler::routes(service);
+    let app = {{Handler}}::routes(service, test_auth());
 
     let updated_{{name}} = json!({
         "id": 1,
-        "name": "Updated {{Name}}",
+{% for field in fields %}
+        "{{ field.name }}": {% if field.name == label_field %}"Updated {{Name}}"{% else %}{{ field.json_sample }}{% endif %},
+{% endfor %}
         "created_at": null,
         "updated_at": null
     });
@@ -91,7 +98,8 @@ async fn test_update_{{name}}() {
         .oneshot(
             Request::builder()
                 .method("PUT")
-                .uri("/{{name}}s/1")
+                .uri("/{{route}}/1")
+                .header("authorization", bearer_token())
                 .header("content-type", "application/json")
                 .body(Body::from(serde_json::to_string(&updated_{{name}}).unwrap()))
                 .unwrap(),
@@ -105,14 +113,15 @@ async fn test_update_{{name}}() {
 #[tokio::test]
 async fn test_delete_{{name}}() {
     let service = Arc::new(create_test_service());
-    let app = {{Name}}Handler::routes(service);
+    let app = {{Handler}}::routes(service, test_auth());
 
     let response = app
         .clone()
         .oneshot(
             Request::builder()
                 .method("DELETE")
-                .uri("/{{name}}s/1")
+                .uri("/{{route}}/1")
+                .header("authorization", bearer_token())
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -125,13 +134,14 @@ async fn test_delete_{{name}}() {
 #[tokio::test]
 async fn test_get_{{name}}_not_found() {
     let service = Arc::new(create_test_service());
-    let app = {{Name}}Handler::routes(service);
+    let app = {{Handler}}::routes(service, test_auth());
 
     let response = app
         .clone()
         .oneshot(
             Request::builder()
-                .uri("/{{name}}s/999")
+                .uri("/{{route}}/999")
+                .header("authorization", bearer_token())
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -140,3 +150,100 @@ async fn test_get_{{name}}_not_found() {
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
+
+#[tokio::test]
+async fn test_{{names}}_require_token() {
+    let service = Arc::new(create_test_service());
+    let app = {{Handler}}::routes(service, test_auth());
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/{{route}}")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_{{names}}_reject_invalid_token() {
+    let service = Arc::new(create_test_service());
+    let app = {{Handler}}::routes(service, test_auth());
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/{{route}}")
+                .header("authorization", "Bearer not-a-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+{# A token without the roles the operation requires gets a 403 #}
+{% macro forbidden_test(operation, method, uri, id) %}
+#[tokio::test]
+async fn test_{{ operation }}_without_role_is_forbidden() {
+    let service = Arc::new(create_test_service());
+    let app = {{Handler}}::routes(service, test_auth());
+{% if id %}
+
+    let {{name}} = json!({
+        "id": {{ id }},
+{% for field in fields %}
+        "{{ field.name }}": {% if field.name == label_field %}"Test {{Name}}"{% else %}{{ field.json_sample }}{% endif %},
+{% endfor %}
+        "created_at": null,
+        "updated_at": null
+    });
+{% endif %}
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("{{ method }}")
+                .uri("{{ uri }}")
+                .header("authorization", bearer_token_with(&[]))
+{% if id %}
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&{{name}}).unwrap()))
+{% else %}
+                .body(Body::empty())
+{% endif %}
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+{%- endmacro %}
+{% if permissions.list %}
+
+{{ forbidden_test("get_all_" ~ names, "GET", "/" ~ route, none) }}
+{% endif %}
+{% if permissions.get %}
+
+{{ forbidden_test("get_" ~ name ~ "_by_id", "GET", "/" ~ route ~ "/1", none) }}
+{% endif %}
+{% if permissions.create %}
+
+{{ forbidden_test("create_" ~ name, "POST", "/" ~ route, 3) }}
+{% endif %}
+{% if permissions.update %}
+
+{{ forbidden_test("update_" ~ name, "PUT", "/" ~ route ~ "/1", 1) }}
+{% endif %}
+{% if permissions.delete %}
+
+{{ forbidden_test("delete_" ~ name, "DELETE", "/" ~ route ~ "/1", none) }}
+{% endif %}
@@ -1,103 +1,16 @@
+{% from "partials/test_data.rs.tpl" import mock_repository, sample_data %}
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::sync::Arc;
-    use crate::usecase::{{name}}_usecase::{{Name}}Usecase;
-    use crate::data::{{name}}_data::{{Name}}Data;
-    use crate::repository::{{name}}::{{Name}}Repository;
+    use crate::{{usecase_mod}}::{{Usecase}};
+    use crate::{{data_mod}}::{{Data}};
+    use crate::{{repository_mod}}::{{Repository}};
     use crate::error::{Result, AppError};
     use async_trait::async_trait;
 
     // Mock repository for testing
-    struct Mock{{Name}}Repository {
-        mock_data: Vec<{{Name}}Data>,
-    }
-
-    impl Mock{{Name}}Repository {
-        fn new() -> Self {
-            Self {
-                mock_data: vec![
-                    {{Name}}Data {
-                        id: 1,
-                        name: "Test {{Name}} 1".to_string(),
-                        created_at: Some(chrono::Utc::now()),
-                        updated_at: Some(chrono::Utc::now()),
-                    },
-                    {{Name}}Data {
-                        id: 2,
-                        name: "Test {{Name}} 2".to_string(),
-                        created_at: Some(chrono::Utc::now()),
-                        updated_at: Some(chrono::Utc::now()),
-                    },
-                ],
-            }
-        }
-    }
-
-    #[async_trait]
-    impl {{Name}}Repository for Mock{{Name}}Repository {
-        async fn find_by_id(&self, id: i64) -> Result<{{Name}}Data> {
-            self.mock_data
-                .iter()
-                .find(|d| d.id == id)
-                .cloned()
-                .ok_or_else(|| AppError::NotFound(format!("{{Name}} with id {} not found", id)))
-        }
-
-        async fn find_all(&self) -> Result<Vec<{{Name}}Data>> {
-            Ok(self.mock_data.clone())
-        }
-
-        async fn find_all_paginated(&self, _limit: i64, _offset: i64) -> Result<Vec<{{Name}}Data>> {
-            Ok(self.mock_data.clone())
-        }
-
-        async fn find_by_name(&self, name: &str) -> Result<Vec<{{Name}}Data>> {
-            Ok(self.mock_data.iter()
-                .filter(|d| d.name == name)
-                .cloned()
-                .collect())
-        }
-
-        async fn search(&self, query: &str) -> Result<Vec<{{Name}}Data>> {
-            Ok(self.mock_data.iter()
-                .filter(|d| d.name.contains(query))
-                .cloned()
-                .collect())
-        }
-
-        async fn count(&self) -> Result<i64> {
-            Ok(self.mock_data.len() as i64)
-        }
-
-        async fn save(&self, data: &{{Name}}Data) -> Result<{{Name}}Data> {
-            Ok(data.clone())
-        }
-
-        async fn save_many(&self, data: &[{{Name}}Data]) -> Result<Vec<{{Name}}Data>> {
-            Ok(data.to_vec())
-        }
-
-        async fn update(&self, id: i64, data: &{{Name}}Data) -> Result<{{Name}}Data> {
-            let mut updated = data.clone();
-            updated.id = id;
-            Ok(updated)
-        }
-
-        async fn delete(&self, _id: i64) -> Result<()> {
-            Ok(())
-        }
-
-        async fn delete_many(&self, _ids: &[i64]) -> Result<u64> {
-            Ok(_ids.len() as u64)
-        }
-    }
-
-    fn create_test_service() -> {{Name}}Service {
-        let repository = Arc::new(Mock{{Name}}Repository::new());
-        let usecase = Arc::new({{Name}}Usecase::new(repository));
-        {{Name}}Service::new(usecase)
-    }
+    {{ mock_repository("") | indent(4) }}
 
     #[tokio::test]
     async fn test_get_by_id_success() {
@@ -107,7 +20,7 @@ mod tests {
         assert!(result.is_ok());
         let data = result.unwrap();
         assert_eq!(data.id, 1);
-        assert_eq!(data.name, "Test {{Name}} 1");
+        assert_eq!(data.{{label_field}}, "Test {{Name}} 1");
     }
 
     #[tokio::test]
@@ -131,35 +44,25 @@ mod tests {
     #[tokio::test]
     async fn test_create() {
         let service = create_test_service();
-        let new_data = {{Name}}Data {
-            id: 3,
-            name: "New {{Name}}".to_string(),
-            created_at: Some(chrono::Utc::now()),
-            updated_at: Some(chrono::Utc::now()),
-        };
+        let new_data = {{ sample_data(3, "New " ~ Name) | indent(8) }};
         
         let result = service.create(new_data.clone()).await;
         
         assert!(result.is_ok());
         let created = result.unwrap();
-        assert_eq!(created.name, new_data.name);
+        assert_eq!(created.{{label_field}}, new_data.{{label_field}});
     }
 
     #[tokio::test]
     async fn test_update() {
         let service = create_test_service();
-        let updated_data = {{Name}}Data {
-            id: 1,
-            name: "Updated {{Name}}".to_string(),
-            created_at: Some(chrono::Utc::now()),
-            updated_at: Some(chrono::Utc::now()),
-        };
+        let updated_data = {{ sample_data(1, "Updated " ~ Name) | indent(8) }};
         
         let result = service.update(1, updated_data.clone()).await;
         
         assert!(result.is_ok());
         let updated = result.unwrap();
-        assert_eq!(updated.name, updated_data.name);
+        assert_eq!(updated.{{label_field}}, updated_data.{{label_field}});
     }
 
     #[tokio::test]
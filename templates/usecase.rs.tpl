@@ -1,37 +1,45 @@
 use std::sync::Arc;
-use crate::repository::{{name}}::{{Name}}Repository;
-use crate::data::{{name}}_data::{{Name}}Data;
+use crate::{{repository_mod}}::{{Repository}};
+use crate::{{data_mod}}::{{Data}};
 use crate::error::{AppError, Result};
 
-pub struct {{Name}}Usecase {
-    repository: Arc<dyn {{Name}}Repository>,
+pub struct {{Usecase}} {
+    repository: Arc<dyn {{Repository}}>,
 }
 
-impl {{Name}}Usecase {
-    pub fn new(repository: Arc<dyn {{Name}}Repository>) -> Self {
+impl {{Usecase}} {
+    pub fn new(repository: Arc<dyn {{Repository}}>) -> Self {
         Self { repository }
     }
 
-    pub async fn get_by_id(&self, id: i64) -> Result<{{Name}}Data> {
+    pub async fn get_by_id(&self, id: i64) -> Result<{{Data}}> {
         self.repository.find_by_id(id).await
     }
 
-    pub async fn get_all(&self) -> Result<Vec<{{Name}}Data>> {
+    pub async fn get_all(&self) -> Result<Vec<{{Data}}>> {
         self.repository.find_all().await
     }
 
-    pub async fn create(&self, data: {{Name}}Data) -> Result<{{Name}}Data> {
+    pub async fn search(&self, query: &str) -> Result<Vec<{{Data}}>> {
+        self.repository.search(query).await
+    }
+
+    pub async fn count(&self) -> Result<i64> {
+        self.repository.count().await
+    }
+
+    pub async fn create(&self, data: {{Data}}) -> Result<{{Data}}> {
         // Add business logic validation here if needed
-        if data.name.is_empty() {
-            return Err(AppError::Validation("Name cannot be empty".to_string()));
+        if data.{{label_field}}.is_empty() {
+            return Err(AppError::Validation("{{label_title}} cannot be empty".to_string()));
         }
         self.repository.save(&data).await
     }
 
-    pub async fn update(&self, id: i64, data: {{Name}}Data) -> Result<{{Name}}Data> {
+    pub async fn update(&self, id: i64, data: {{Data}}) -> Result<{{Data}}> {
         // Add business logic validation here if needed
-        if data.name.is_empty() {
-            return Err(AppError::Validation("Name cannot be empty".to_string()));
+        if data.{{label_field}}.is_empty() {
+            return Err(AppError::Validation("{{label_title}} cannot be empty".to_string()));
         }
         self.repository.update(id, &data).await
     }
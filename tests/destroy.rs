@@ -0,0 +1,46 @@
+// End-to-end checks that run the rvy binary against a fresh project and then
+// build it. They compile every generated dependency, so they are ignored by
+// default: `cargo test --test destroy -- --ignored`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn scratch_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rvy-e2e-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn rvy(dir: &Path, args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rvy")).args(args).current_dir(dir).output().unwrap();
+    assert!(
+        output.status.success(),
+        "rvy {} failed:\n{}{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn cargo_build(dir: &Path) {
+    let output = Command::new(env!("CARGO")).arg("build").current_dir(dir).output().unwrap();
+    assert!(output.status.success(), "cargo build failed:\n{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+#[ignore = "builds a generated project"]
+fn project_builds_after_destroying_the_last_grpc_entity() {
+    let root = scratch_dir("grpc");
+    rvy(&root, &["new", "project", "app", "--db", "sqlite", "--web", "actix"]);
+    let project = root.join("app");
+    rvy(&project, &["gen-all", "item", "--field", "name:string"]);
+    rvy(&project, &["gen", "grpc", "item"]);
+
+    rvy(&project, &["destroy", "item"]);
+
+    assert!(!project.join("proto/item.proto").exists());
+    assert!(project.join("build.rs").exists());
+    cargo_build(&project);
+    std::fs::remove_dir_all(&root).unwrap();
+}